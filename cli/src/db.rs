@@ -0,0 +1,199 @@
+use chrono::{DateTime, Utc};
+use pomododragon::{HistoryEntry, PomoState};
+use rusqlite::{params, Connection, Row};
+use std::path::Path;
+
+/// Migrations from each schema version to the next
+/// the index is the version a migration starts from
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE history (
+        id INTEGER PRIMARY KEY,
+        state TEXT NOT NULL,
+        start TEXT NOT NULL,
+        end TEXT NOT NULL,
+        task TEXT,
+        skipped INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX history_start ON history (start);",
+    // a phase completed unless it was skipped or paused
+    "ALTER TABLE history ADD COLUMN completed INTEGER NOT NULL DEFAULT 0;
+    UPDATE history SET completed = 1 WHERE id IN (
+        SELECT id FROM (
+            SELECT id, state, task, skipped, LEAD(state) OVER (ORDER BY start) AS next
+            FROM history
+        )
+        WHERE state != 'Paused' AND skipped = 0
+            AND (task IS NOT NULL OR (next IS NOT NULL AND next != 'Paused'))
+    );",
+];
+
+/// Long term history of all phases in a sqlite database
+pub struct HistoryDb {
+    conn: Connection,
+}
+
+impl HistoryDb {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        if let Some(parent) = path.parent() {
+            // opening reports the error if this fails
+            let _ = std::fs::create_dir_all(parent);
+        }
+        Self::new(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(conn: Connection) -> rusqlite::Result<Self> {
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            conn.execute_batch(migration)?;
+            conn.pragma_update(None, "user_version", i as i64 + 1)?;
+        }
+        Ok(Self { conn })
+    }
+
+    pub fn insert(&self, entry: &HistoryEntry) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO history (state, start, end, task, skipped, completed)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                format!("{:?}", entry.state),
+                entry.start,
+                entry.end,
+                entry.task,
+                entry.skipped,
+                entry.completed
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// all entries that started in the range from..to
+    pub fn entries(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> rusqlite::Result<Vec<HistoryEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT state, start, end, task, skipped, completed FROM history
+            WHERE start >= ?1 AND start < ?2 ORDER BY start",
+        )?;
+        let rows = stmt.query_map(params![from, to], Self::entry)?;
+        rows.collect()
+    }
+
+    fn entry(row: &Row) -> rusqlite::Result<HistoryEntry> {
+        let state: String = row.get(0)?;
+        Ok(HistoryEntry {
+            state: parse_state(&state),
+            start: row.get(1)?,
+            end: row.get(2)?,
+            task: row.get(3)?,
+            skipped: row.get(4)?,
+            completed: row.get(5)?,
+        })
+    }
+}
+
+/// states are stored by their variant name
+fn parse_state(state: &str) -> PomoState {
+    match state {
+        "Working" => PomoState::Working,
+        "Break" => PomoState::Break,
+        "LongBreak" => PomoState::LongBreak,
+        "Paused" => PomoState::Paused,
+        "Pending" => PomoState::Pending,
+        "Completed" => PomoState::Completed,
+        _ => PomoState::NotStarted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 12, 24, hour, 0, 0).unwrap()
+    }
+
+    fn entry(hour: u32, task: Option<&str>) -> HistoryEntry {
+        entry_in(PomoState::Working, hour, task)
+    }
+
+    fn entry_in(state: PomoState, hour: u32, task: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            state,
+            start: at(hour),
+            end: at(hour + 1),
+            task: task.map(|task| task.into()),
+            skipped: false,
+            completed: true,
+        }
+    }
+
+    #[test]
+    fn it_should_query_by_range() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        db.insert(&entry(8, None)).unwrap();
+        db.insert(&entry(10, Some("Task1"))).unwrap();
+        db.insert(&entry(12, None)).unwrap();
+
+        assert_eq!(
+            db.entries(at(9), at(12)).unwrap(),
+            vec![entry(10, Some("Task1"))]
+        );
+    }
+
+    #[test]
+    fn it_should_migrate_completed_phases() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        for (state, hour) in [
+            ("Working", 8),
+            ("Paused", 9),
+            ("Working", 10),
+            ("Break", 11),
+        ] {
+            conn.execute(
+                "INSERT INTO history (state, start, end) VALUES (?1, ?2, ?3)",
+                params![state, at(hour), at(hour + 1)],
+            )
+            .unwrap();
+        }
+
+        let db = HistoryDb::new(conn).unwrap();
+        let entries = db.entries(at(8), at(12)).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.completed)
+                .collect::<Vec<_>>(),
+            vec![false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn it_should_keep_the_state() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let states = [
+            PomoState::Working,
+            PomoState::Break,
+            PomoState::LongBreak,
+            PomoState::Paused,
+        ];
+        for (i, state) in states.into_iter().enumerate() {
+            db.insert(&entry_in(state, 8 + i as u32, None)).unwrap();
+        }
+
+        let entries = db.entries(at(8), at(12)).unwrap();
+        assert_eq!(
+            entries.iter().map(|entry| entry.state).collect::<Vec<_>>(),
+            states
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use pomododragon::{
+    Actor, ObservedPomo, PomoCommand, PomoData, PomoMessage, PomoState, SimplePomo, SimpleTask,
+    Timer,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::mpsc;
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+pub type HttpPomo<TTimer> = ObservedPomo<SimpleTask, SimplePomo<SimpleTask, TTimer>>;
+
+/// status code, message of the executed command and json body
+pub type Routed = (u16, PomoMessage<SimpleTask>, String);
+
+#[derive(Deserialize)]
+struct TaskBody {
+    description: String,
+}
+
+/// Accepts requests on a separate thread.
+/// The requests are answered by the thread that owns the pomo
+pub fn serve(server: Server) -> mpsc::Receiver<Request> {
+    let (requests, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            if requests.send(request).is_err() {
+                return;
+            }
+        }
+    });
+
+    receiver
+}
+
+/// Answers a request and returns the message of the executed command
+pub fn respond<TTimer: Timer>(
+    pomo: &mut HttpPomo<TTimer>,
+    mut request: Request,
+) -> PomoMessage<SimpleTask> {
+    let mut body = String::new();
+    let (status, message, json) = if is_foreign(&request) {
+        // other sites must not control the timer through the browser
+        error(403, "foreign origin")
+    } else {
+        match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => route(pomo, request.method(), request.url(), &body),
+            Err(_) => error(400, "unreadable body"),
+        }
+    };
+
+    let mut response = Response::from_string(json).with_status_code(status);
+    if let Ok(header) = Header::from_bytes("Content-Type", "application/json") {
+        response.add_header(header);
+    }
+    if let Err(err) = request.respond(response) {
+        eprintln!("{}", err);
+    }
+
+    message
+}
+
+/// Maps a request to a command and executes it.
+/// Every command responds with the status of the pomo
+pub fn route<TTimer: Timer>(
+    pomo: &mut HttpPomo<TTimer>,
+    method: &Method,
+    url: &str,
+    body: &str,
+) -> Routed {
+    let path = url.split('?').next().unwrap_or_default();
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    let command = match (method, segments.as_slice()) {
+        (Method::Get, ["status"]) => return (200, PomoMessage::NoMessage, status(pomo)),
+        (Method::Post, ["start"]) => PomoCommand::Start,
+        (Method::Post, ["pause"]) => PomoCommand::Pause,
+        (Method::Post, ["resume"]) => PomoCommand::Unpause,
+        (Method::Post, ["reset"]) => PomoCommand::Reset,
+        (Method::Post, ["skip", state]) => match parse_state(state) {
+            Some(state) => PomoCommand::SkipTo(state),
+            None => return error(400, "unknown state"),
+        },
+        (Method::Get, ["tasks"]) => {
+            return (200, PomoMessage::NoMessage, json!(pomo.tasks()).to_string())
+        }
+        (Method::Post, ["tasks"]) => match parse_task(body) {
+            Some(task) => PomoCommand::AddTask(task),
+            None => return error(400, "expected {\"description\": \"...\"}"),
+        },
+        (_, ["tasks", index]) => {
+            let index = match index.parse::<usize>() {
+                Ok(index) if index < pomo.tasks().len() => index,
+                _ => return error(404, "unknown task"),
+            };
+            match method {
+                Method::Get => {
+                    return (
+                        200,
+                        PomoMessage::NoMessage,
+                        json!(pomo.tasks()[index]).to_string(),
+                    )
+                }
+                Method::Put => match parse_task(body) {
+                    Some(task) => PomoCommand::UpdateTask(index, task),
+                    None => return error(400, "expected {\"description\": \"...\"}"),
+                },
+                Method::Delete => PomoCommand::RemoveTask(index),
+                _ => return error(405, "method not allowed"),
+            }
+        }
+        _ => return error(404, "not found"),
+    };
+
+    let message = pomo.execute(command);
+    (200, message, status(pomo))
+}
+
+/// true if a browser sent the request from a page of another origin
+fn is_foreign(request: &Request) -> bool {
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(name))
+            .map(|header| header.value.as_str())
+    };
+
+    match header("Origin") {
+        Some(origin) => {
+            let host = origin
+                .strip_prefix("http://")
+                .or_else(|| origin.strip_prefix("https://"));
+            host.is_none() || host != header("Host")
+        }
+        None => false,
+    }
+}
+
+fn status<TTimer: Timer>(pomo: &HttpPomo<TTimer>) -> String {
+    json!(pomo.pomo().snapshot()).to_string()
+}
+
+fn error(status: u16, error: &str) -> Routed {
+    (
+        status,
+        PomoMessage::NoMessage,
+        json!({ "error": error }).to_string(),
+    )
+}
+
+fn parse_task(body: &str) -> Option<SimpleTask> {
+    serde_json::from_str::<TaskBody>(body)
+        .ok()
+        .map(|body| SimpleTask::new(&body.description))
+}
+
+fn parse_state(state: &str) -> Option<PomoState> {
+    match state.to_lowercase().as_str() {
+        "working" | "work" => Some(PomoState::Working),
+        "break" => Some(PomoState::Break),
+        "longbreak" | "long-break" => Some(PomoState::LongBreak),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pomododragon::InstantTimer;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    fn pomo() -> HttpPomo<InstantTimer> {
+        ObservedPomo::new(SimplePomo::default())
+    }
+
+    #[test]
+    fn it_should_route_commands() {
+        let mut pomo = pomo();
+
+        let (status, _, body) = route(
+            &mut pomo,
+            &Method::Post,
+            "/tasks",
+            "{\"description\": \"a\"}",
+        );
+        assert_eq!(status, 200);
+        assert!(body.contains("\"description\":\"a\""));
+
+        let (status, message, _) = route(&mut pomo, &Method::Post, "/skip/working", "");
+        assert_eq!(status, 200);
+        assert!(matches!(message, PomoMessage::Transition(_)));
+        assert_eq!(pomo.state(), PomoState::Working);
+
+        let (status, _, _) = route(
+            &mut pomo,
+            &Method::Put,
+            "/tasks/0",
+            "{\"description\": \"b\"}",
+        );
+        assert_eq!(status, 200);
+        assert_eq!(pomo.tasks(), &[SimpleTask::new("b")]);
+
+        let (status, _, _) = route(&mut pomo, &Method::Delete, "/tasks/1", "");
+        assert_eq!(status, 404);
+        let (status, _, _) = route(&mut pomo, &Method::Delete, "/tasks/0", "");
+        assert_eq!(status, 200);
+        assert!(pomo.tasks().is_empty());
+
+        let (status, _, _) = route(&mut pomo, &Method::Post, "/skip/nowhere", "");
+        assert_eq!(status, 400);
+        let (status, _, _) = route(&mut pomo, &Method::Get, "/unknown", "");
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn it_should_answer_local_clients() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let address = server.server_addr().to_ip().unwrap();
+        let requests = serve(server);
+
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            write!(
+                stream,
+                "POST /start HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+                address
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+
+        let mut pomo = pomo();
+        respond(&mut pomo, requests.recv().unwrap());
+        assert_eq!(pomo.state(), PomoState::Pending);
+
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("\"state\":\"Pending\""));
+    }
+
+    #[test]
+    fn it_should_reject_foreign_origins() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let address = server.server_addr().to_ip().unwrap();
+        let requests = serve(server);
+
+        let client = thread::spawn(move || {
+            [
+                "http://evil.example".to_string(),
+                format!("http://{}", address),
+            ]
+            .map(|origin| {
+                let mut stream = TcpStream::connect(address).unwrap();
+                write!(
+                    stream,
+                    "POST /start HTTP/1.1\r\nHost: {}\r\nOrigin: {}\r\nConnection: close\r\n\r\n",
+                    address, origin
+                )
+                .unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                response
+            })
+        });
+
+        let mut pomo = pomo();
+        respond(&mut pomo, requests.recv().unwrap());
+        assert_eq!(pomo.state(), PomoState::NotStarted);
+        respond(&mut pomo, requests.recv().unwrap());
+        assert_eq!(pomo.state(), PomoState::Pending);
+
+        let [foreign, local] = client.join().unwrap();
+        assert!(foreign.starts_with("HTTP/1.1 403"));
+        assert!(local.starts_with("HTTP/1.1 200"));
+    }
+}
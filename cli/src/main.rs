@@ -1,59 +1,507 @@
-use clap::Parser;
+mod db;
+mod http;
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use clap::{Args, Parser, Subcommand};
+use db::HistoryDb;
 use spinners::{Spinner, Spinners};
+use std::cell::RefCell;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
 use pomododragon::{
-    Actor, InstantTimer, PomoCommand, PomoData, SimplePomoBuilder, SimpleTask, TimeParser, Timer,
+    Actor, Goal, HistoryEntry, HistoryFormat, HistoryRecorder, JsonFileStore, ObservedPomo,
+    PomoCommand, PomoData, PomoMessage, PomoSnapshot, PomoState, Preset, ResumePolicy, Settings,
+    SimplePomoBuilder, SimpleTask, Store, StoreData, SystemTimer, TaskFormat, TimeParser, Timer,
+    UntilParseError, UntilParser, WireMessage,
 };
 
+/// the number of cycles when there is no store to take it from
+const TOTAL_CYCLES: usize = 6;
+
 #[derive(Parser, Debug)]
-#[clap(version = "0.1.0", author = "Lukas Krickl <lukas@krickl.dev>")]
+#[clap(
+    version = "0.1.0",
+    author = "Lukas Krickl <lukas@krickl.dev>",
+    args_conflicts_with_subcommands = true
+)]
 struct Opts {
-    #[clap(short, long, default_value = "5m")]
-    break_time: String,
-    #[clap(short, long, default_value = "25m")]
-    work_time: String,
-    #[clap(short, long, default_value = "30m")]
-    long_break_time: String,
-
-    #[clap(short, long, default_value = "10")]
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// sqlite database every phase is recorded in
+    /// defaults to history.sqlite3 in the data directory
+    #[clap(long, global = true)]
+    db: Option<PathBuf>,
+
+    /// json file tasks, settings and history are loaded from and saved to
+    #[clap(long, global = true)]
+    store: Option<PathBuf>,
+
+    /// use the settings of a preset, the other options override it
+    #[clap(long)]
+    preset: Option<String>,
+
+    /// defaults to 5m
+    #[clap(short, long, parse(try_from_str = TimeParser::parse))]
+    break_time: Option<Duration>,
+    /// a duration or an absolute time like "until 14:30"
+    /// that only applies to the first work phase.
+    /// A bare 14:30 is 14m30s.
+    /// defaults to 25m
+    #[clap(short, long, parse(try_from_str = parse_goal))]
+    work_time: Option<Goal>,
+    /// defaults to 30m
+    #[clap(short, long, parse(try_from_str = TimeParser::parse))]
+    long_break_time: Option<Duration>,
+
+    /// what to do when the system resumes from suspend
+    /// during a phase: complete, pause or continue
+    #[clap(short, long, default_value = "continue")]
+    on_resume: ResumePolicy,
+
+    /// join a shared session instead of running locally
+    /// e.g. ws://127.0.0.1:9090
+    #[clap(long)]
+    join: Option<String>,
+
+    /// serve a json api for integrations on --http-address
+    #[clap(long, conflicts_with = "join")]
+    http: bool,
+    #[clap(long, default_value = "127.0.0.1:7878")]
+    http_address: SocketAddr,
+
+    /// maximum time between display updates
+    #[clap(short, long, default_value = "250")]
     poll_millis: u64,
 
-    #[clap(short, long, default_value = "4")]
-    until_break: usize,
-    #[clap(short, long, default_value = "6")]
-    total: usize,
+    /// defaults to 4
+    #[clap(short, long)]
+    until_break: Option<usize>,
+    /// defaults to 6, or the total of the store
+    #[clap(short, long)]
+    total: Option<usize>,
 
     tasks: Vec<String>,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// lists recorded phases, defaults to today
+    Log(DateRange),
+    /// writes recorded phases to stdout, defaults to everything
+    Export {
+        #[clap(flatten)]
+        range: DateRange,
+        /// csv, ical or timew, ical and timew only contain work phases
+        #[clap(long, default_value = "csv")]
+        format: HistoryFormat,
+    },
+    /// imports or exports the tasks of the store
+    Tasks {
+        #[clap(subcommand)]
+        command: TasksCommand,
+    },
+    /// lists, imports or exports presets
+    Presets {
+        #[clap(subcommand)]
+        command: PresetsCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PresetsCommand {
+    /// lists the builtin and stored presets
+    List,
+    /// adds the presets of a json file to the store,
+    /// presets with the same name are replaced
+    Import { file: PathBuf },
+    /// writes all presets as json to a file or stdout
+    Export { file: Option<PathBuf> },
+}
+
+#[derive(Subcommand, Debug)]
+enum TasksCommand {
+    /// appends the tasks of a todo.txt or markdown checklist file
+    Import {
+        file: PathBuf,
+        /// todo.txt or markdown, guessed from the file name by default
+        #[clap(long)]
+        format: Option<TaskFormat>,
+    },
+    /// writes all tasks to a file or stdout
+    Export {
+        file: Option<PathBuf>,
+        /// todo.txt or markdown, guessed from the file name by default
+        #[clap(long)]
+        format: Option<TaskFormat>,
+    },
+}
+
+#[derive(Args, Debug)]
+struct DateRange {
+    /// first day to include
+    #[clap(long)]
+    from: Option<NaiveDate>,
+    /// last day to include
+    #[clap(long)]
+    to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    const FIRST_DAY: NaiveDate = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+    const LAST_DAY: NaiveDate = NaiveDate::from_ymd_opt(9999, 12, 31).unwrap();
+
+    /// start of from until the end of to in utc,
+    /// without to the range is open ended and without from it starts at the first day.
+    /// default_from is only used when neither is given
+    fn bounds(&self, default_from: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        let from = match (self.from, self.to) {
+            (Some(from), _) => from,
+            (None, Some(_)) => Self::FIRST_DAY,
+            (None, None) => default_from,
+        };
+        let to = self.to.and_then(|to| to.succ_opt());
+        (
+            Self::midnight(from),
+            Self::midnight(to.unwrap_or(Self::LAST_DAY)),
+        )
+    }
+
+    fn midnight(date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        Local.from_local_datetime(&midnight).earliest().map_or_else(
+            || Utc.from_utc_datetime(&midnight),
+            |x| x.with_timezone(&Utc),
+        )
+    }
+}
+
+fn default_db() -> Option<PathBuf> {
+    Some(
+        dirs::data_dir()?
+            .join("pomododragon")
+            .join("history.sqlite3"),
+    )
+}
+
+fn open_db(path: Option<PathBuf>) -> Option<HistoryDb> {
+    let path = path.or_else(default_db)?;
+    match HistoryDb::open(&path) {
+        Ok(db) => Some(db),
+        Err(err) => {
+            eprintln!("Unable to open {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+fn query(db: Option<HistoryDb>, range: &DateRange, default_from: NaiveDate) -> Vec<HistoryEntry> {
+    let (from, to) = range.bounds(default_from);
+    let entries = db.map(|db| db.entries(from, to));
+    match entries {
+        Some(Ok(entries)) => entries,
+        Some(Err(err)) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        None => std::process::exit(1),
+    }
+}
+
+fn log(db: Option<HistoryDb>, range: &DateRange) {
+    for entry in query(db, range, Local::now().date_naive()) {
+        let start = entry.start.with_timezone(&Local);
+        let end = entry.end.with_timezone(&Local);
+        let duration = entry.duration().to_std().unwrap_or_default();
+
+        println!(
+            "{} {} - {} [{}] [{}] {}{}",
+            start.format("%Y-%m-%d"),
+            start.format("%H:%M"),
+            end.format("%H:%M"),
+            entry.state,
+            TimeParser::format_clock(duration),
+            entry.task.as_deref().unwrap_or(""),
+            if entry.skipped { " (skipped)" } else { "" }
+        );
+    }
+}
+
+fn export(db: Option<HistoryDb>, range: &DateRange, format: HistoryFormat) {
+    let entries = query(db, range, DateRange::FIRST_DAY);
+    print!("{}", format.format(&entries));
+}
+
+fn tasks(store: Option<PathBuf>, command: &TasksCommand) {
+    let mut store = match store {
+        Some(store) => Some(JsonFileStore::new(store)),
+        None => {
+            eprintln!("Tasks are kept in the store, use --store <FILE>");
+            std::process::exit(1);
+        }
+    };
+    let mut data = load(&store);
+
+    match command {
+        TasksCommand::Import { file, format } => {
+            let format =
+                format.unwrap_or_else(|| TaskFormat::from_file_name(&file.to_string_lossy()));
+            let input = std::fs::read_to_string(file).unwrap_or_else(|err| {
+                eprintln!("Unable to read {}: {}", file.display(), err);
+                std::process::exit(1);
+            });
+            data.tasks.append(&mut format.parse(&input));
+            save(&mut store, &data);
+        }
+        TasksCommand::Export { file, format } => {
+            let format = format.unwrap_or_else(|| {
+                file.as_ref().map_or(TaskFormat::TodoTxt, |file| {
+                    TaskFormat::from_file_name(&file.to_string_lossy())
+                })
+            });
+            let output = format.format(&data.tasks);
+            match file {
+                Some(file) => {
+                    if let Err(err) = std::fs::write(file, output) {
+                        eprintln!("Unable to write {}: {}", file.display(), err);
+                        std::process::exit(1);
+                    }
+                }
+                None => print!("{}", output),
+            }
+        }
+    }
+}
+
+fn presets(store: Option<PathBuf>, command: &PresetsCommand) {
+    let mut store = store.map(JsonFileStore::new);
+    let mut data = load(&store);
+
+    match command {
+        PresetsCommand::List => {
+            for preset in data.presets() {
+                let settings = &preset.settings;
+                println!(
+                    "{}: {}/{}/{}, long break after {}, {} cycles",
+                    preset.name,
+                    TimeParser::format(settings.work_time),
+                    TimeParser::format(settings.short_break_time),
+                    TimeParser::format(settings.long_break_time),
+                    settings.cycles_until_long_break,
+                    settings.total_cycles
+                );
+            }
+        }
+        PresetsCommand::Import { file } => {
+            if store.is_none() {
+                eprintln!("Presets are kept in the store, use --store <FILE>");
+                std::process::exit(1);
+            }
+            let imported = std::fs::read_to_string(file)
+                .map_err(|err| format!("Unable to read {}: {}", file.display(), err))
+                .and_then(|input| Preset::import(&input).map_err(|err| err.to_string()))
+                .unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                });
+            Preset::merge(&mut data.presets, imported);
+            save(&mut store, &data);
+        }
+        PresetsCommand::Export { file } => {
+            let output = Preset::export(&data.presets()).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            });
+            match file {
+                Some(file) => {
+                    if let Err(err) = std::fs::write(file, output) {
+                        eprintln!("Unable to write {}: {}", file.display(), err);
+                        std::process::exit(1);
+                    }
+                }
+                None => println!("{}", output),
+            }
+        }
+    }
+}
+
+/// Follows a shared session on a separate thread.
+/// The session is started if nobody did yet and the tasks are added to it
+fn join(url: String, tasks: Vec<SimpleTask>) -> mpsc::Receiver<PomoSnapshot<SimpleTask>> {
+    let (snapshots, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut socket = match tungstenite::connect(url.as_str()) {
+            Ok((socket, _)) => socket,
+            Err(err) => {
+                eprintln!("Unable to join {}: {}", url, err);
+                return;
+            }
+        };
+
+        let mut commands = tasks
+            .into_iter()
+            .map(PomoCommand::AddTask)
+            .collect::<Vec<_>>();
+        let mut joined = false;
+        while let Ok(message) = socket.read() {
+            let snapshot = match message {
+                tungstenite::Message::Text(text) => match WireMessage::from_json(&text) {
+                    Ok(WireMessage::Snapshot(snapshot)) => snapshot,
+                    Ok(_) => continue,
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        continue;
+                    }
+                },
+                _ => continue,
+            };
+
+            // only the first snapshot decides whether to start,
+            // later ones may not have seen the start yet
+            if !joined && snapshot.state == PomoState::NotStarted {
+                commands.push(PomoCommand::Start);
+            }
+            joined = true;
+            for command in commands.drain(..) {
+                let command = WireMessage::Command(command).to_json().unwrap_or_default();
+                if socket.send(tungstenite::Message::text(command)).is_err() {
+                    return;
+                }
+            }
+
+            if snapshots.send(snapshot).is_err() {
+                return;
+            }
+        }
+    });
+
+    receiver
+}
+
+fn parse_goal(goal: &str) -> Result<Goal, UntilParseError> {
+    UntilParser::default().parse_goal(goal)
+}
+
+fn load(store: &Option<JsonFileStore>) -> StoreData {
+    match store {
+        Some(store) => store.load().unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }),
+        None => StoreData::default(),
+    }
+}
+
+fn save(store: &mut Option<JsonFileStore>, data: &StoreData) {
+    if let Some(store) = store {
+        if let Err(err) = store.save(data) {
+            eprintln!("{}", err);
+        }
+    }
+}
+
 fn main() {
     let stdout = std::io::stdout();
     let opts: Opts = Opts::parse();
 
-    let mut tasks = vec![];
-
-    for s in opts.tasks {
-        tasks.push(SimpleTask::new(&s));
-    }
-
-    let mut pomo = SimplePomoBuilder::<SimpleTask, InstantTimer>::default()
-        .break_timer(InstantTimer::new(
-            TimeParser::parse(&opts.break_time).expect("Unable to parse time"),
-        ))
-        .work_timer(InstantTimer::new(
-            TimeParser::parse(&opts.work_time).expect("Unable to parse time"),
-        ))
-        .long_break_timer(InstantTimer::new(
-            TimeParser::parse(&opts.long_break_time).expect("Unable to parse time"),
-        ))
-        .cycles_until_long_break(opts.until_break)
-        .total_cycles(opts.total)
-        .tasks(tasks)
+    match &opts.command {
+        Some(Command::Log(range)) => return log(open_db(opts.db), range),
+        Some(Command::Export { range, format }) => return export(open_db(opts.db), range, *format),
+        Some(Command::Tasks { command }) => return tasks(opts.store, command),
+        Some(Command::Presets { command }) => return presets(opts.store, command),
+        None => (),
+    }
+    let db = open_db(opts.db);
+
+    let mut store = opts.store.map(JsonFileStore::new);
+    let mut data = load(&store);
+    // without a store the cli keeps its own number of cycles
+    let mut settings = match &store {
+        Some(_) => data.settings.clone(),
+        None => Settings {
+            total_cycles: TOTAL_CYCLES,
+            ..Settings::default()
+        },
+    };
+
+    if let Some(name) = &opts.preset {
+        match data
+            .presets()
+            .into_iter()
+            .find(|preset| &preset.name == name)
+        {
+            Some(preset) => settings = preset.settings,
+            None => {
+                eprintln!("Unknown preset '{}', see the presets list command", name);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // options override the stored settings for this run only
+    if let Some(Goal::Duration(goal)) = opts.work_time {
+        settings.work_time = goal;
+    }
+    settings.short_break_time = opts.break_time.unwrap_or(settings.short_break_time);
+    settings.long_break_time = opts.long_break_time.unwrap_or(settings.long_break_time);
+    settings.cycles_until_long_break = opts.until_break.unwrap_or(settings.cycles_until_long_break);
+    settings.total_cycles = opts.total.unwrap_or(settings.total_cycles);
+
+    let tasks = opts
+        .tasks
+        .iter()
+        .map(|task| SimpleTask::new(task))
+        .collect::<Vec<_>>();
+
+    // a shared session has its own tasks
+    let session = opts.join.map(|url| join(url, tasks.clone()));
+    if session.is_none() {
+        data.tasks.extend(tasks);
+    }
+    save(&mut store, &data);
+
+    let pomo = SimplePomoBuilder::<SimpleTask, SystemTimer>::default()
+        .break_timer(SystemTimer::new(settings.short_break_time))
+        .work_timer(SystemTimer::new(settings.work_time))
+        .long_break_timer(SystemTimer::new(settings.long_break_time))
+        .cycles_until_long_break(settings.cycles_until_long_break)
+        .total_cycles(settings.total_cycles)
+        .resume_policy(opts.on_resume)
+        .next_work_goal(match opts.work_time {
+            // an absolute time is not kept as the work time
+            Some(goal @ Goal::Until(_)) => Some(goal),
+            _ => None,
+        })
+        .tasks(data.pending_tasks())
         .build()
         .expect("Unable to build pomo");
 
+    let mut pomo = ObservedPomo::new(pomo);
+    let history = Rc::new(RefCell::new(vec![]));
+    let sink = history.clone();
+    pomo.subscribe(Box::new(HistoryRecorder::new(Box::new(move |entry| {
+        sink.borrow_mut().push(entry)
+    }))));
+
+    let requests = if opts.http {
+        match tiny_http::Server::http(opts.http_address) {
+            Ok(server) => Some(http::serve(server)),
+            Err(err) => {
+                eprintln!("Unable to bind {}: {}", opts.http_address, err);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+    let mut answered = vec![];
+
     let sp = if !termion::is_tty(&stdout) {
         None
     } else {
@@ -61,10 +509,52 @@ fn main() {
         Some(Spinner::new(&Spinners::Dots, "".into()))
     };
 
-    pomo.execute(PomoCommand::Start);
+    if session.is_none() {
+        pomo.execute(PomoCommand::Start);
+    }
 
-    while !pomo.is_completed() {
-        pomo.execute(PomoCommand::Update);
+    // the api keeps the pomo around after it completed
+    while requests.is_some() || !pomo.is_completed() {
+        let mut messages = match &session {
+            // the session decides when to transition
+            Some(snapshots) => match snapshots.try_recv() {
+                Ok(snapshot) => {
+                    let message = pomo.pomo_mut().apply_snapshot(snapshot);
+                    pomo.notify(&message);
+                    vec![message]
+                }
+                Err(mpsc::TryRecvError::Empty) => vec![],
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    eprintln!("Left the session");
+                    break;
+                }
+            },
+            None => vec![pomo.execute(PomoCommand::Update)],
+        };
+        messages.append(&mut answered);
+
+        if messages.iter().any(|message| {
+            matches!(
+                message,
+                PomoMessage::Transition(_)
+                    | PomoMessage::ClockJump(_)
+                    | PomoMessage::Executed
+                    | PomoMessage::Reset
+            )
+        }) {
+            // transitions may complete tasks and finish phases
+            // and the api may change tasks
+            if session.is_none() {
+                data.set_pending_tasks(pomo.tasks());
+            }
+            for entry in history.borrow_mut().drain(..) {
+                if let Some(Err(err)) = db.as_ref().map(|db| db.insert(&entry)) {
+                    eprintln!("{}", err);
+                }
+                data.history.push(entry);
+            }
+            save(&mut store, &data);
+        }
 
         let message = if pomo.is_paused() {
             "Paused".into()
@@ -92,9 +582,63 @@ fn main() {
             println!("{}", message);
         }
 
-        thread::sleep(Duration::from_millis(opts.poll_millis));
+        // sleep until the next transition is due
+        // but wake up in time to refresh the display
+        let poll = Duration::from_millis(opts.poll_millis);
+        let timeout = pomo.next_update().map_or(poll, |next| next.min(poll));
+        match &requests {
+            // requests are answered right away
+            Some(requests) => {
+                if let Ok(request) = requests.recv_timeout(timeout) {
+                    answered.push(http::respond(&mut pomo, request));
+                    answered.extend(requests.try_iter().map(|r| http::respond(&mut pomo, r)));
+                }
+            }
+            None => thread::sleep(timeout),
+        }
     }
     if let Some(sp) = sp {
         sp.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2021, 12, day).unwrap()
+    }
+
+    #[test]
+    fn it_should_bound_date_ranges() {
+        let range = |from, to| DateRange { from, to };
+        assert_eq!(
+            range(Some(day(1)), Some(day(24))).bounds(day(5)),
+            (DateRange::midnight(day(1)), DateRange::midnight(day(25)))
+        );
+        // without to the range is open ended
+        assert_eq!(
+            range(Some(day(1)), None).bounds(day(5)),
+            (
+                DateRange::midnight(day(1)),
+                DateRange::midnight(DateRange::LAST_DAY)
+            )
+        );
+        // without from the range reaches back to the first day
+        assert_eq!(
+            range(None, Some(day(1))).bounds(day(5)),
+            (
+                DateRange::midnight(DateRange::FIRST_DAY),
+                DateRange::midnight(day(2))
+            )
+        );
+        assert_eq!(
+            range(None, None).bounds(day(5)),
+            (
+                DateRange::midnight(day(5)),
+                DateRange::midnight(DateRange::LAST_DAY)
+            )
+        );
+    }
+}
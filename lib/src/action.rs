@@ -1,6 +1,8 @@
-use crate::{PomoState, Task};
+use crate::{serde_duration, PomoState, ResumePolicy, Task};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct Transition<TTask>
 where
     TTask: Task,
@@ -8,6 +10,8 @@ where
     pub from: PomoState,
     pub to: PomoState,
     pub completed: Option<TTask>,
+    /// the phase that was left was skipped before its timer finished
+    pub skipped: bool,
 }
 
 impl<TTask> Transition<TTask>
@@ -19,6 +23,7 @@ where
             from,
             to,
             completed: None,
+            skipped: false,
         }
     }
 
@@ -27,6 +32,7 @@ where
             from,
             to,
             completed: Some(completed),
+            skipped: false,
         }
     }
 }
@@ -49,12 +55,45 @@ where
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+/// Sent when the active timer noticed that the wall-clock
+/// jumped ahead, usually because the system was suspended
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ClockJump<TTask>
+where
+    TTask: Task,
+{
+    #[serde(with = "serde_duration")]
+    pub gap: Duration,
+    pub policy: ResumePolicy,
+    /// the transition the policy caused, if any
+    pub transition: Option<Transition<TTask>>,
+}
+
+impl<TTask> std::fmt::Display for ClockJump<TTask>
+where
+    TTask: Task,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "(gap: {}s, policy: {}, transition: {})",
+            self.gap.as_secs(),
+            self.policy,
+            match &self.transition {
+                Some(transition) => transition.to_string(),
+                None => "None".into(),
+            }
+        )
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum PomoMessage<TTask>
 where
     TTask: Task,
 {
     Transition(Transition<TTask>),
+    ClockJump(ClockJump<TTask>),
     NoMessage,
     Executed,
     Reset,
@@ -70,6 +109,7 @@ where
             "{}",
             match self {
                 Self::Transition(t) => t.to_string(),
+                Self::ClockJump(j) => j.to_string(),
                 Self::NoMessage => "NoMessage".into(),
                 Self::Reset => "Reset".into(),
                 Self::Executed => "Executed".into(),
@@ -78,18 +118,24 @@ where
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum PomoCommand<TTask>
 where
     TTask: Task,
 {
     AddTask(TTask),
     RemoveTask(usize),
+    /// replaces the task at an index
+    UpdateTask(usize, TTask),
+    /// moves the task at the first index to the second,
+    /// the first task is worked on next
+    MoveTask(usize, usize),
     Start,
     Reset,
     Pause,
     Unpause,
     TogglePause,
+    SkipTo(PomoState),
     Update,
     Clear,
 }
@@ -0,0 +1,303 @@
+use crate::{HistoryEntry, PomoState};
+use chrono::{DateTime, Utc};
+
+/// Formats recorded history can be exported as
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// every recorded phase
+    #[default]
+    Csv,
+    /// work sessions as VEVENTs
+    ICalendar,
+    /// work sessions as timewarrior intervals
+    Timewarrior,
+}
+
+impl HistoryFormat {
+    pub fn format(&self, entries: &[HistoryEntry]) -> String {
+        match self {
+            Self::Csv => Self::csv(entries),
+            Self::ICalendar => Self::icalendar(&work_sessions(entries)),
+            Self::Timewarrior => Self::timewarrior(&work_sessions(entries)),
+        }
+    }
+
+    fn csv(entries: &[HistoryEntry]) -> String {
+        let mut out = "state,start,end,seconds,task,skipped\n".to_string();
+        for entry in entries {
+            out.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                entry.state,
+                entry.start.to_rfc3339(),
+                entry.end.to_rfc3339(),
+                entry.duration().num_seconds(),
+                csv_field(entry.task.as_deref().unwrap_or("")),
+                entry.skipped
+            ));
+        }
+        out
+    }
+
+    fn icalendar(sessions: &[HistoryEntry]) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".into(),
+            "PRODID:-//pomododragon//pomododragon//EN".into(),
+        ];
+
+        for session in sessions {
+            let summary = session.task.as_deref().unwrap_or("Work");
+            lines.extend([
+                "BEGIN:VEVENT".into(),
+                format!(
+                    "UID:{}-{}@pomododragon",
+                    basic_time(&session.start),
+                    basic_time(&session.end)
+                ),
+                format!("DTSTAMP:{}", basic_time(&session.end)),
+                format!("DTSTART:{}", basic_time(&session.start)),
+                format!("DTEND:{}", basic_time(&session.end)),
+                format!("SUMMARY:{}", ical_text(summary)),
+                "END:VEVENT".into(),
+            ]);
+        }
+        lines.push("END:VCALENDAR".into());
+
+        lines.iter().map(|line| fold(line) + "\r\n").collect()
+    }
+
+    fn timewarrior(sessions: &[HistoryEntry]) -> String {
+        sessions
+            .iter()
+            .map(|session| {
+                let interval = format!(
+                    "inc {} - {}",
+                    basic_time(&session.start),
+                    basic_time(&session.end)
+                );
+                match &session.task {
+                    Some(task) => format!("{} # {}\n", interval, timew_tag(task)),
+                    None => format!("{}\n", interval),
+                }
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for HistoryFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Csv => "csv",
+                Self::ICalendar => "ical",
+                Self::Timewarrior => "timew",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for HistoryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "ical" | "ics" | "icalendar" => Ok(Self::ICalendar),
+            "timew" | "timewarrior" => Ok(Self::Timewarrior),
+            _ => Err(format!("Unknown history format '{}'", s)),
+        }
+    }
+}
+
+/// Completed working phases of the history.
+/// A work phase that was interrupted by pauses is recorded in parts,
+/// the parts are kept once the last part completed
+/// and are tagged with the task it completed.
+pub fn work_sessions(entries: &[HistoryEntry]) -> Vec<HistoryEntry> {
+    let mut sessions: Vec<HistoryEntry> = vec![];
+    // parts of a phase that was not completed yet
+    let mut parts: Vec<HistoryEntry> = vec![];
+
+    for (i, entry) in entries.iter().enumerate() {
+        // the parts only continue after a pause, a reset drops them
+        if entry.state == PomoState::Working && i > 0 && entries[i - 1].state != PomoState::Paused {
+            parts.clear();
+        }
+
+        match entry.state {
+            PomoState::Working if entry.completed => {
+                for mut part in parts.drain(..) {
+                    if entry.task.is_some() {
+                        part.task = entry.task.clone();
+                    }
+                    sessions.push(part);
+                }
+                sessions.push(entry.clone());
+            }
+            PomoState::Working if !entry.skipped => parts.push(entry.clone()),
+            PomoState::Paused => (),
+            _ => parts.clear(),
+        }
+    }
+    sessions
+}
+
+/// utc time in the basic iso 8601 format
+fn basic_time(time: &DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// quotes a csv field if required
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into()
+    }
+}
+
+fn ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// folds content lines longer than 75 octets
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        len += c.len_utf8();
+        folded.push(c);
+    }
+    folded
+}
+
+fn timew_tag(tag: &str) -> String {
+    format!("\"{}\"", tag.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 12, 24, 10, min, 0).unwrap()
+    }
+
+    fn entry(state: PomoState, start: u32, end: u32, task: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            state,
+            start: at(start),
+            end: at(end),
+            task: task.map(|task| task.into()),
+            skipped: false,
+            completed: false,
+        }
+    }
+
+    fn completed(state: PomoState, start: u32, end: u32, task: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            completed: true,
+            ..entry(state, start, end, task)
+        }
+    }
+
+    fn history() -> Vec<HistoryEntry> {
+        vec![
+            entry(PomoState::Working, 0, 10, None),
+            entry(PomoState::Paused, 10, 12, None),
+            completed(PomoState::Working, 12, 27, Some("Say \"hi\", then leave")),
+            completed(PomoState::Break, 27, 32, None),
+            completed(PomoState::Working, 32, 57, None),
+        ]
+    }
+
+    #[test]
+    fn it_should_collect_work_sessions() {
+        assert_eq!(
+            work_sessions(&history()),
+            vec![
+                entry(PomoState::Working, 0, 10, Some("Say \"hi\", then leave")),
+                completed(PomoState::Working, 12, 27, Some("Say \"hi\", then leave")),
+                completed(PomoState::Working, 32, 57, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_not_collect_unfinished_work() {
+        let skipped = HistoryEntry {
+            skipped: true,
+            ..entry(PomoState::Working, 0, 10, Some("Skipped"))
+        };
+        let history = vec![
+            skipped,
+            entry(PomoState::Break, 10, 15, None),
+            // paused, then reset
+            entry(PomoState::Working, 15, 20, None),
+            entry(PomoState::Paused, 20, 25, None),
+            entry(PomoState::Working, 25, 30, None),
+            completed(PomoState::Working, 30, 55, Some("Done")),
+        ];
+        assert_eq!(
+            work_sessions(&history),
+            vec![completed(PomoState::Working, 30, 55, Some("Done"))]
+        );
+    }
+
+    #[test]
+    fn it_should_export_csv() {
+        assert_eq!(
+            HistoryFormat::Csv.format(&history()[2..4]),
+            "state,start,end,seconds,task,skipped\n\
+            Working,2021-12-24T10:12:00+00:00,2021-12-24T10:27:00+00:00,900,\
+            \"Say \"\"hi\"\", then leave\",false\n\
+            Break,2021-12-24T10:27:00+00:00,2021-12-24T10:32:00+00:00,300,,false\n"
+        );
+    }
+
+    #[test]
+    fn it_should_export_timewarrior() {
+        assert_eq!(
+            HistoryFormat::Timewarrior.format(&history()[2..]),
+            "inc 20211224T101200Z - 20211224T102700Z # \"Say \\\"hi\\\", then leave\"\n\
+            inc 20211224T103200Z - 20211224T105700Z\n"
+        );
+    }
+
+    #[test]
+    fn it_should_export_icalendar() {
+        assert_eq!(
+            HistoryFormat::ICalendar.format(&history()[2..3]),
+            "BEGIN:VCALENDAR\r\n\
+            VERSION:2.0\r\n\
+            PRODID:-//pomododragon//pomododragon//EN\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:20211224T101200Z-20211224T102700Z@pomododragon\r\n\
+            DTSTAMP:20211224T102700Z\r\n\
+            DTSTART:20211224T101200Z\r\n\
+            DTEND:20211224T102700Z\r\n\
+            SUMMARY:Say \"hi\"\\, then leave\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n"
+        );
+    }
+
+    #[test]
+    fn it_should_fold_long_lines() {
+        let line = format!("SUMMARY:{}", "a".repeat(100));
+        let folded = fold(&line);
+        assert!(folded.split("\r\n").all(|line| line.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{PomoObserver, PomoState, Task, Transition};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A phase the pomo machine spent time in
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Working, Break, LongBreak or Paused
+    pub state: PomoState,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// the task that was completed at the end of the phase
+    #[serde(default)]
+    pub task: Option<String>,
+    /// the phase was skipped before its timer finished
+    #[serde(default)]
+    pub skipped: bool,
+    /// the timer of the phase finished,
+    /// a phase that was paused only completes with its last entry
+    #[serde(default)]
+    pub completed: bool,
+}
+
+impl HistoryEntry {
+    pub fn duration(&self) -> chrono::Duration {
+        self.end - self.start
+    }
+}
+
+/// Turns transitions into history entries
+/// Every finished phase is passed to the sink
+pub struct HistoryRecorder {
+    current: Option<(PomoState, DateTime<Utc>)>,
+    sink: Box<dyn FnMut(HistoryEntry)>,
+}
+
+impl HistoryRecorder {
+    pub fn new(sink: Box<dyn FnMut(HistoryEntry)>) -> Self {
+        Self {
+            current: None,
+            sink,
+        }
+    }
+
+    /// records a transition that happened at now
+    pub fn record<TTask>(&mut self, transition: &Transition<TTask>, now: DateTime<Utc>)
+    where
+        TTask: Task,
+    {
+        if let Some((state, start)) = self.current.take() {
+            if state == transition.from {
+                (self.sink)(HistoryEntry {
+                    state,
+                    start,
+                    end: now,
+                    task: transition.completed.as_ref().map(|task| task.to_string()),
+                    skipped: transition.skipped,
+                    completed: state != PomoState::Paused
+                        && transition.to != PomoState::Paused
+                        && !transition.skipped,
+                });
+            }
+        }
+
+        if Self::is_recorded(transition.to) {
+            self.current = Some((transition.to, now));
+        }
+    }
+
+    /// records the running phase as cancelled at now
+    pub fn reset(&mut self, now: DateTime<Utc>) {
+        if let Some((state, start)) = self.current.take() {
+            (self.sink)(HistoryEntry {
+                state,
+                start,
+                end: now,
+                task: None,
+                skipped: false,
+                completed: false,
+            });
+        }
+    }
+
+    fn is_recorded(state: PomoState) -> bool {
+        matches!(
+            state,
+            PomoState::Working | PomoState::Break | PomoState::LongBreak | PomoState::Paused
+        )
+    }
+}
+
+impl<TTask> PomoObserver<TTask> for HistoryRecorder
+where
+    TTask: Task,
+{
+    fn on_transition(&mut self, transition: &Transition<TTask>) {
+        self.record(transition, Utc::now());
+    }
+
+    fn on_reset(&mut self) {
+        self.reset(Utc::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleTask;
+    use chrono::TimeZone;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn at(min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 12, 24, 9, min, 0).unwrap()
+    }
+
+    #[test]
+    fn it_should_record_phases() {
+        let entries = Rc::new(RefCell::new(vec![]));
+        let sink = entries.clone();
+        let mut recorder = HistoryRecorder::new(Box::new(move |entry| {
+            sink.borrow_mut().push(entry);
+        }));
+
+        let mut task = SimpleTask::new("Task1");
+        task.complete();
+
+        recorder.record::<SimpleTask>(
+            &Transition::new(PomoState::NotStarted, PomoState::Pending),
+            at(0),
+        );
+        recorder.record::<SimpleTask>(
+            &Transition::new(PomoState::Pending, PomoState::Working),
+            at(0),
+        );
+        recorder.record::<SimpleTask>(
+            &Transition::new(PomoState::Working, PomoState::Paused),
+            at(10),
+        );
+        recorder.record::<SimpleTask>(
+            &Transition::new(PomoState::Paused, PomoState::Working),
+            at(12),
+        );
+        recorder.record(
+            &Transition::new_task(PomoState::Working, PomoState::Break, task),
+            at(27),
+        );
+        let mut skip = Transition::new(PomoState::Break, PomoState::Working);
+        skip.skipped = true;
+        recorder.record::<SimpleTask>(&skip, at(30));
+
+        assert_eq!(
+            *entries.borrow(),
+            vec![
+                HistoryEntry {
+                    state: PomoState::Working,
+                    start: at(0),
+                    end: at(10),
+                    task: None,
+                    skipped: false,
+                    completed: false,
+                },
+                HistoryEntry {
+                    state: PomoState::Paused,
+                    start: at(10),
+                    end: at(12),
+                    task: None,
+                    skipped: false,
+                    completed: false,
+                },
+                HistoryEntry {
+                    state: PomoState::Working,
+                    start: at(12),
+                    end: at(27),
+                    task: Some("Task1".into()),
+                    skipped: false,
+                    completed: true,
+                },
+                HistoryEntry {
+                    state: PomoState::Break,
+                    start: at(27),
+                    end: at(30),
+                    task: None,
+                    skipped: true,
+                    completed: false,
+                },
+            ]
+        );
+        assert_eq!(
+            entries.borrow()[2].duration(),
+            chrono::Duration::minutes(15)
+        );
+    }
+
+    #[test]
+    fn it_should_record_reset_phases() {
+        let entries = Rc::new(RefCell::new(vec![]));
+        let sink = entries.clone();
+        let mut recorder = HistoryRecorder::new(Box::new(move |entry| {
+            sink.borrow_mut().push(entry);
+        }));
+
+        recorder.record::<SimpleTask>(
+            &Transition::new(PomoState::Pending, PomoState::Working),
+            at(0),
+        );
+        recorder.reset(at(10));
+        recorder.reset(at(12));
+
+        assert_eq!(
+            *entries.borrow(),
+            vec![HistoryEntry {
+                state: PomoState::Working,
+                start: at(0),
+                end: at(10),
+                task: None,
+                skipped: false,
+                completed: false,
+            }]
+        );
+    }
+}
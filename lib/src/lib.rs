@@ -1,11 +1,37 @@
 mod action;
 mod command;
+mod export;
+mod history;
+mod observer;
+mod policy;
 mod pomo;
+mod preset;
+#[cfg(feature = "runner")]
+mod runner;
+mod snapshot;
+mod stats;
+mod store;
 mod task;
+mod task_format;
 mod time;
+mod until;
+mod wire;
 
 pub use action::*;
 pub use command::*;
+pub use export::*;
+pub use history::*;
+pub use observer::*;
+pub use policy::*;
 pub use pomo::*;
+pub use preset::*;
+#[cfg(feature = "runner")]
+pub use runner::*;
+pub use snapshot::*;
+pub use stats::*;
+pub use store::*;
 pub use task::*;
+pub use task_format::*;
 pub use time::*;
+pub use until::*;
+pub use wire::*;
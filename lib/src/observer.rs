@@ -0,0 +1,258 @@
+use crate::{
+    Actor, ClockJump, PomoCommand, PomoData, PomoMessage, PomoState, Task, Timer, Transition,
+};
+
+/// Observers are notified about everything an observed
+/// pomo machine does.
+/// All events are optional, an observer only has to implement
+/// the events it is interested in
+pub trait PomoObserver<TTask>
+where
+    TTask: Task,
+{
+    /// called for every transition, including pause and resume
+    fn on_transition(&mut self, _transition: &Transition<TTask>) {}
+
+    /// called when a working phase completed a task
+    fn on_task_completed(&mut self, _task: &TTask) {}
+
+    /// called when the machine is paused, state is the paused phase
+    fn on_pause(&mut self, _state: PomoState) {}
+
+    /// called when the machine is resumed, state is the resumed phase
+    fn on_resume(&mut self, _state: PomoState) {}
+
+    fn on_reset(&mut self) {}
+
+    /// called for every update command
+    fn on_tick(&mut self) {}
+}
+
+pub type ObserverId = usize;
+
+/// Wraps a pomo machine and dispatches the result of every
+/// executed command to all subscribed observers
+pub struct ObservedPomo<TTask, TPomo>
+where
+    TTask: Task,
+{
+    pomo: TPomo,
+    observers: Vec<(ObserverId, Box<dyn PomoObserver<TTask>>)>,
+    next_id: ObserverId,
+}
+
+impl<TTask, TPomo> ObservedPomo<TTask, TPomo>
+where
+    TTask: Task,
+{
+    pub fn new(pomo: TPomo) -> Self {
+        Self {
+            pomo,
+            observers: vec![],
+            next_id: 0,
+        }
+    }
+
+    pub fn pomo(&self) -> &TPomo {
+        &self.pomo
+    }
+
+    /// Commands executed directly on the inner machine
+    /// are not dispatched to the observers!
+    pub fn pomo_mut(&mut self) -> &mut TPomo {
+        &mut self.pomo
+    }
+
+    /// registers an observer
+    /// the returned id can be used to unsubscribe
+    pub fn subscribe(&mut self, observer: Box<dyn PomoObserver<TTask>>) -> ObserverId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.observers.push((id, observer));
+        id
+    }
+
+    pub fn unsubscribe(&mut self, id: ObserverId) -> Option<Box<dyn PomoObserver<TTask>>> {
+        let index = self.observers.iter().position(|(x, _)| *x == id)?;
+        Some(self.observers.remove(index).1)
+    }
+
+    /// dispatches a message to all observers
+    pub fn notify(&mut self, message: &PomoMessage<TTask>) {
+        match message {
+            PomoMessage::Transition(transition)
+            | PomoMessage::ClockJump(ClockJump {
+                transition: Some(transition),
+                ..
+            }) => {
+                for (_, observer) in self.observers.iter_mut() {
+                    observer.on_transition(transition);
+
+                    if let Some(task) = &transition.completed {
+                        observer.on_task_completed(task);
+                    }
+
+                    if transition.to == PomoState::Paused && transition.from != PomoState::Paused {
+                        observer.on_pause(transition.from);
+                    } else if transition.from == PomoState::Paused
+                        && transition.to != PomoState::Paused
+                    {
+                        observer.on_resume(transition.to);
+                    }
+                }
+            }
+            PomoMessage::Reset => {
+                for (_, observer) in self.observers.iter_mut() {
+                    observer.on_reset();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl<TTask, TPomo> Actor<PomoCommand<TTask>, PomoMessage<TTask>> for ObservedPomo<TTask, TPomo>
+where
+    TTask: Task,
+    TPomo: Actor<PomoCommand<TTask>, PomoMessage<TTask>>,
+{
+    fn execute(&mut self, command: PomoCommand<TTask>) -> PomoMessage<TTask> {
+        let tick = matches!(command, PomoCommand::Update);
+        let message = self.pomo.execute(command);
+
+        if tick {
+            for (_, observer) in self.observers.iter_mut() {
+                observer.on_tick();
+            }
+        }
+        self.notify(&message);
+        message
+    }
+}
+
+impl<TTask, TTimer, TPomo> PomoData<TTask, TTimer> for ObservedPomo<TTask, TPomo>
+where
+    TTask: Task,
+    TTimer: Timer,
+    TPomo: PomoData<TTask, TTimer>,
+{
+    fn state(&self) -> PomoState {
+        self.pomo.state()
+    }
+
+    fn timer(&self) -> Option<&TTimer> {
+        self.pomo.timer()
+    }
+
+    fn task(&self) -> Option<&TTask> {
+        self.pomo.task()
+    }
+
+    fn tasks(&self) -> &[TTask] {
+        self.pomo.tasks()
+    }
+
+    fn tasks_mut(&mut self) -> &mut [TTask] {
+        self.pomo.tasks_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use crate::{InstantTimer, SimplePomo, SimplePomoBuilder, SimpleTask};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl PomoObserver<SimpleTask> for Recorder {
+        fn on_transition(&mut self, transition: &Transition<SimpleTask>) {
+            self.events
+                .borrow_mut()
+                .push(format!("transition {}", transition));
+        }
+
+        fn on_task_completed(&mut self, task: &SimpleTask) {
+            self.events.borrow_mut().push(format!("completed {}", task));
+        }
+
+        fn on_pause(&mut self, state: PomoState) {
+            self.events.borrow_mut().push(format!("pause {}", state));
+        }
+
+        fn on_resume(&mut self, state: PomoState) {
+            self.events.borrow_mut().push(format!("resume {}", state));
+        }
+
+        fn on_reset(&mut self) {
+            self.events.borrow_mut().push("reset".into());
+        }
+
+        fn on_tick(&mut self) {
+            self.events.borrow_mut().push("tick".into());
+        }
+    }
+
+    #[test]
+    fn it_should_dispatch_events() {
+        let pomo = SimplePomoBuilder::<SimpleTask, InstantTimer>::default()
+            .work_timer(InstantTimer::new(Duration::from_millis(10)))
+            .tasks(vec![SimpleTask::new("Task1")])
+            .build()
+            .unwrap();
+        let mut pomo = ObservedPomo::new(pomo);
+
+        let events = Rc::new(RefCell::new(vec![]));
+        pomo.subscribe(Box::new(Recorder {
+            events: events.clone(),
+        }));
+
+        pomo.execute(PomoCommand::Start);
+        pomo.execute(PomoCommand::Update);
+        pomo.execute(PomoCommand::Pause);
+        pomo.execute(PomoCommand::Unpause);
+        std::thread::sleep(Duration::from_millis(20));
+        pomo.execute(PomoCommand::Update);
+        pomo.execute(PomoCommand::Reset);
+
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                "transition (from: NotStarted, to: Pending, completed: None)",
+                "tick",
+                "transition (from: Pending, to: Working, completed: None)",
+                "transition (from: Working, to: Paused, completed: None)",
+                "pause Working",
+                "transition (from: Paused, to: Working, completed: None)",
+                "resume Working",
+                "tick",
+                "transition (from: Working, to: Break, completed: Task1)",
+                "completed Task1",
+                "reset",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_unsubscribe() {
+        let mut pomo = ObservedPomo::new(SimplePomo::<SimpleTask, InstantTimer>::default());
+
+        let events = Rc::new(RefCell::new(vec![]));
+        let id = pomo.subscribe(Box::new(Recorder {
+            events: events.clone(),
+        }));
+        pomo.execute(PomoCommand::Update);
+        assert!(pomo.unsubscribe(id).is_some());
+        assert!(pomo.unsubscribe(id).is_none());
+        pomo.execute(PomoCommand::Update);
+
+        assert_eq!(*events.borrow(), vec!["tick"]);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::PomoState;
+use derive_builder::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Summary of a phase that just ended
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PhaseReport {
+    pub state: PomoState,
+    pub goal: Duration,
+    pub elapsed: Duration,
+    pub pauses: usize,
+    pub skipped: bool,
+}
+
+impl PhaseReport {
+    /// how long the phase ran past its goal
+    pub fn overtime(&self) -> Duration {
+        self.elapsed.saturating_sub(self.goal)
+    }
+}
+
+/// A phase policy decides the goal of each phase
+/// the pomo machine enters.
+/// The machine reports every phase that ends and asks
+/// the policy for the goal of the next phase.
+pub trait PhasePolicy: Clone + Default {
+    /// called whenever a working or break phase ends,
+    /// either because it completed or because it was skipped
+    fn phase_ended(&mut self, _report: &PhaseReport) {}
+
+    /// returns the goal for the next phase
+    /// goal is the goal of the timer that is about to be started
+    fn goal(&mut self, _state: PomoState, goal: Duration) -> Duration {
+        goal
+    }
+}
+
+/// Always uses the timers' goals as they are
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixedPolicy;
+
+impl PhasePolicy for FixedPolicy {}
+
+/// Lengthens breaks after work phases with pauses or overtime
+/// and shortens work phases after a skipped break
+#[derive(Builder, Clone, Debug, PartialEq)]
+#[builder(setter(into))]
+pub struct AdaptivePolicy {
+    /// added to the next break for every pause during work
+    #[builder(default = "Duration::from_secs(60)")]
+    pub pause_bonus: Duration,
+    /// fraction of the work overtime that is added to the next break
+    #[builder(default = "0.2")]
+    pub overtime_factor: f64,
+    /// upper bound for the time added to a break
+    #[builder(default = "Duration::from_secs(60 * 10)")]
+    pub max_break_bonus: Duration,
+    /// removed from the next work phase after a skipped break
+    #[builder(default = "Duration::from_secs(60 * 5)")]
+    pub skip_penalty: Duration,
+    /// work phases are never shortened below this
+    #[builder(default = "Duration::from_secs(60 * 5)")]
+    pub min_work: Duration,
+
+    #[builder(setter(skip))]
+    break_bonus: Duration,
+    #[builder(setter(skip))]
+    work_cut: Duration,
+}
+
+impl Default for AdaptivePolicy {
+    fn default() -> Self {
+        AdaptivePolicyBuilder::default()
+            .build()
+            .expect("Default adaptive policy is valid")
+    }
+}
+
+impl PhasePolicy for AdaptivePolicy {
+    fn phase_ended(&mut self, report: &PhaseReport) {
+        match report.state {
+            PomoState::Working if !report.skipped => {
+                let bonus = self.pause_bonus * report.pauses as u32
+                    + report.overtime().mul_f64(self.overtime_factor);
+                self.break_bonus = bonus.min(self.max_break_bonus);
+            }
+            PomoState::Working => self.break_bonus = Duration::ZERO,
+            PomoState::Break | PomoState::LongBreak if report.skipped => {
+                self.work_cut = self.skip_penalty;
+            }
+            _ => (),
+        }
+    }
+
+    fn goal(&mut self, state: PomoState, goal: Duration) -> Duration {
+        match state {
+            PomoState::Working => {
+                let cut = std::mem::take(&mut self.work_cut);
+                goal.saturating_sub(cut).max(self.min_work.min(goal))
+            }
+            PomoState::Break | PomoState::LongBreak => goal + std::mem::take(&mut self.break_bonus),
+            _ => goal,
+        }
+    }
+}
+
+/// What the machine does when the system resumes from a suspend
+/// or the wall-clock jumps ahead while a phase is running
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResumePolicy {
+    /// end the interrupted phase right away
+    Complete,
+    /// do not count the suspended time and pause
+    Pause,
+    /// count the suspended time and keep going
+    #[default]
+    Continue,
+}
+
+impl std::fmt::Display for ResumePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Complete => "complete",
+                Self::Pause => "pause",
+                Self::Continue => "continue",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for ResumePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "complete" => Ok(Self::Complete),
+            "pause" => Ok(Self::Pause),
+            "continue" => Ok(Self::Continue),
+            _ => Err(format!("Unknown resume policy '{}'", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work_report(elapsed: u64, pauses: usize) -> PhaseReport {
+        PhaseReport {
+            state: PomoState::Working,
+            goal: Duration::from_secs(60 * 25),
+            elapsed: Duration::from_secs(elapsed),
+            pauses,
+            skipped: false,
+        }
+    }
+
+    #[test]
+    fn it_should_keep_goals_with_fixed_policy() {
+        let mut policy = FixedPolicy;
+        policy.phase_ended(&work_report(60 * 30, 3));
+        assert_eq!(
+            policy.goal(PomoState::Break, Duration::from_secs(300)),
+            Duration::from_secs(300)
+        );
+    }
+
+    #[test]
+    fn it_should_lengthen_break_after_pauses_and_overtime() {
+        let mut policy = AdaptivePolicy::default();
+        policy.phase_ended(&work_report(60 * 30, 2));
+
+        // 2 pauses + 20% of 5 minutes overtime
+        assert_eq!(
+            policy.goal(PomoState::Break, Duration::from_secs(300)),
+            Duration::from_secs(300 + 120 + 60)
+        );
+        // bonus is only applied once
+        assert_eq!(
+            policy.goal(PomoState::Break, Duration::from_secs(300)),
+            Duration::from_secs(300)
+        );
+    }
+
+    #[test]
+    fn it_should_cap_break_bonus() {
+        let mut policy = AdaptivePolicy::default();
+        policy.phase_ended(&work_report(60 * 25, 100));
+        assert_eq!(
+            policy.goal(PomoState::LongBreak, Duration::from_secs(1800)),
+            Duration::from_secs(1800 + 600)
+        );
+    }
+
+    #[test]
+    fn it_should_shorten_work_after_skipped_break() {
+        let mut policy = AdaptivePolicy::default();
+        policy.phase_ended(&PhaseReport {
+            state: PomoState::Break,
+            goal: Duration::from_secs(300),
+            elapsed: Duration::from_secs(10),
+            pauses: 0,
+            skipped: true,
+        });
+        assert_eq!(
+            policy.goal(PomoState::Working, Duration::from_secs(60 * 25)),
+            Duration::from_secs(60 * 20)
+        );
+        assert_eq!(
+            policy.goal(PomoState::Working, Duration::from_secs(60 * 25)),
+            Duration::from_secs(60 * 25)
+        );
+    }
+
+    #[test]
+    fn it_should_not_shorten_work_below_minimum() {
+        let mut policy = AdaptivePolicy {
+            work_cut: Duration::from_secs(60 * 5),
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.goal(PomoState::Working, Duration::from_secs(60 * 7)),
+            Duration::from_secs(60 * 5)
+        );
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{Actor, PomoCommand, PomoMessage, Task, Timer, Transition};
+use crate::{
+    Actor, ClockJump, FixedPolicy, Goal, PhasePolicy, PhaseReport, PomoCommand, PomoMessage,
+    PomoSnapshot, ResumePolicy, Task, Timer, TimerSnapshot, Transition,
+};
 use derive_builder::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Pomo is a simple state machine
 /// with a timer and an output interface
@@ -50,6 +55,15 @@ where
     fn is_completed(&self) -> bool {
         self.state() == PomoState::Completed
     }
+
+    /// time until the machine has to be updated again
+    /// None if no update is required until the next command
+    fn next_update(&self) -> Option<Duration> {
+        match self.state() {
+            PomoState::Pending => Some(Duration::from_secs(0)),
+            _ => self.timer().and_then(|timer| timer.remaining()),
+        }
+    }
 }
 
 pub trait PomoActions<TTask, TTimer>
@@ -72,8 +86,9 @@ where
 }
 
 /// All possible states a pomo machine can be in
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub enum PomoState {
+    #[default]
     NotStarted,
     Pending,
     Working,
@@ -101,20 +116,17 @@ impl std::fmt::Display for PomoState {
     }
 }
 
-impl Default for PomoState {
-    fn default() -> Self {
-        Self::NotStarted
-    }
-}
-
 /// A simple state machine
 /// with a timer
+/// The phase policy is consulted for the goal of every
+/// phase, by default the timers' goals are used as is
 #[derive(Builder, Debug, Clone)]
-#[builder(setter(into))]
-pub struct SimplePomo<TTask, TTimer>
+#[builder(setter(into), name = "GenericSimplePomoBuilder")]
+pub struct SimplePomo<TTask, TTimer, TPolicy = FixedPolicy>
 where
     TTask: Task,
     TTimer: Timer,
+    TPolicy: PhasePolicy,
 {
     #[builder(default)]
     pub tasks: Vec<TTask>,
@@ -137,12 +149,31 @@ where
     pub state: PomoState, // internal state
     #[builder(default = "PomoState::default()")]
     pub prev_state: PomoState,
+
+    #[builder(default)]
+    pub policy: TPolicy,
+    // pauses during the current phase
+    #[builder(default = "0")]
+    pub pauses: usize,
+
+    #[builder(default)]
+    pub resume_policy: ResumePolicy,
+
+    /// replaces the goal of the next work phase only,
+    /// e.g. to end it at an absolute time
+    #[builder(default)]
+    pub next_work_goal: Option<Goal>,
 }
 
-impl<TTask, TTimer> Default for SimplePomo<TTask, TTimer>
+/// Builder for SimplePomo that falls back to the fixed policy
+pub type SimplePomoBuilder<TTask, TTimer, TPolicy = FixedPolicy> =
+    GenericSimplePomoBuilder<TTask, TTimer, TPolicy>;
+
+impl<TTask, TTimer, TPolicy> Default for SimplePomo<TTask, TTimer, TPolicy>
 where
     TTask: Task,
     TTimer: Timer,
+    TPolicy: PhasePolicy,
 {
     fn default() -> Self {
         Self::new(
@@ -154,10 +185,11 @@ where
     }
 }
 
-impl<TTask, TTimer> SimplePomo<TTask, TTimer>
+impl<TTask, TTimer, TPolicy> SimplePomo<TTask, TTimer, TPolicy>
 where
     TTask: Task,
     TTimer: Timer,
+    TPolicy: PhasePolicy,
 {
     pub fn new(
         tasks: Vec<TTask>,
@@ -175,45 +207,221 @@ where
             current_cycles: 0,
             state: PomoState::default(),
             prev_state: PomoState::default(),
+            policy: TPolicy::default(),
+            pauses: 0,
+            resume_policy: ResumePolicy::default(),
+            next_work_goal: None,
+        }
+    }
+
+    /// the phase that is currently running, even if it is paused
+    fn active_phase(&self) -> PomoState {
+        if self.is_paused() {
+            self.prev_state
+        } else {
+            self.state()
+        }
+    }
+
+    fn phase_timer(&mut self, state: PomoState) -> Option<&mut TTimer> {
+        match state {
+            PomoState::Working => Some(&mut self.work_timer),
+            PomoState::Break => Some(&mut self.break_timer),
+            PomoState::LongBreak => Some(&mut self.long_break_timer),
+            _ => None,
         }
     }
 
+    /// captures the state of the machine and its active timer
+    pub fn snapshot(&self) -> PomoSnapshot<TTask> {
+        let timer = match self.active_phase() {
+            PomoState::Working => Some(&self.work_timer),
+            PomoState::Break => Some(&self.break_timer),
+            PomoState::LongBreak => Some(&self.long_break_timer),
+            _ => None,
+        };
+
+        PomoSnapshot {
+            state: self.state,
+            prev_state: self.prev_state,
+            tasks: self.tasks.clone(),
+            current_cycles: self.current_cycles,
+            cycles_until_long_break: self.cycles_until_long_break,
+            total_cycles: self.total_cycles,
+            timer: timer.and_then(|timer| Some((timer, timer.elapsed()?))).map(
+                |(timer, elapsed)| TimerSnapshot {
+                    elapsed: elapsed.saturating_sub(timer.paused_for().unwrap_or_default()),
+                    goal: timer.goal(),
+                    paused: timer.is_paused(),
+                },
+            ),
+        }
+    }
+
+    /// Makes this machine mirror a snapshot.
+    /// Returns the transition if the snapshot is in a different state
+    pub fn apply_snapshot(&mut self, snapshot: PomoSnapshot<TTask>) -> PomoMessage<TTask> {
+        let from = self.state;
+
+        // a finished work phase removed the first task
+        let completed = if snapshot.current_cycles > self.current_cycles
+            && snapshot.tasks.len() < self.tasks.len()
+        {
+            self.tasks.first().cloned().map(|mut task| {
+                task.complete();
+                task
+            })
+        } else {
+            None
+        };
+
+        self.state = snapshot.state;
+        self.prev_state = snapshot.prev_state;
+        self.tasks = snapshot.tasks;
+        self.current_cycles = snapshot.current_cycles;
+        self.cycles_until_long_break = snapshot.cycles_until_long_break;
+        self.total_cycles = snapshot.total_cycles;
+
+        let phase = self.active_phase();
+        if let (Some(timer), Some(restored)) = (self.phase_timer(phase), snapshot.timer) {
+            timer.restore(restored.elapsed, restored.goal, restored.paused);
+        }
+
+        if from == self.state {
+            PomoMessage::NoMessage
+        } else {
+            PomoMessage::Transition(Transition {
+                from,
+                to: self.state,
+                completed,
+                skipped: false,
+            })
+        }
+    }
+
+    /// Advances the machine by gap as if it had been updated all along,
+    /// e.g. after a snapshot of a closed session was applied.
+    /// Returns the transitions of every phase that ended in between
+    pub fn fast_forward(&mut self, mut gap: Duration) -> Vec<PomoMessage<TTask>> {
+        let mut messages = vec![];
+        if self.state == PomoState::Pending {
+            messages.push(self.enter(PomoState::Working));
+        }
+
+        // a paused machine has no running phase
+        while let Some(timer) = self.phase_timer(self.state) {
+            let goal = timer.goal();
+            let elapsed = timer.elapsed().unwrap_or_default();
+            let remaining = goal.saturating_sub(elapsed);
+            if gap < remaining {
+                timer.restore(elapsed + gap, goal, false);
+                break;
+            }
+
+            gap -= remaining;
+            timer.restore(goal, goal, false);
+            messages.push(match self.state {
+                PomoState::Working => self.complete_working(),
+                _ => self.complete_break(),
+            });
+        }
+        messages
+    }
+
+    /// tells the policy that the active phase ended
+    fn end_phase(&mut self, skipped: bool) {
+        let state = self.active_phase();
+        let timer = match state {
+            PomoState::Working => &self.work_timer,
+            PomoState::Break => &self.break_timer,
+            PomoState::LongBreak => &self.long_break_timer,
+            _ => return,
+        };
+
+        let report = PhaseReport {
+            state,
+            goal: timer.goal(),
+            elapsed: timer.elapsed().unwrap_or_else(|| Duration::from_secs(0)),
+            pauses: self.pauses,
+            skipped,
+        };
+        self.policy.phase_ended(&report);
+    }
+
+    /// Stops all timers, starts the timer for state with the goal
+    /// the policy picks and transitions to state
+    fn enter(&mut self, state: PomoState) -> PomoMessage<TTask> {
+        self.work_timer.reset();
+        self.break_timer.reset();
+        self.long_break_timer.reset();
+        self.pauses = 0;
+
+        let timer = match state {
+            PomoState::Working => Some(&mut self.work_timer),
+            PomoState::Break => Some(&mut self.break_timer),
+            PomoState::LongBreak => Some(&mut self.long_break_timer),
+            _ => None,
+        };
+
+        if let Some(timer) = timer {
+            let goal = self.policy.goal(state, timer.goal());
+            let goal = match state {
+                // an absolute time is converted when the phase starts
+                PomoState::Working => self
+                    .next_work_goal
+                    .take()
+                    .map_or(goal, |goal| goal.duration()),
+                _ => goal,
+            };
+            timer.start_with_goal(goal);
+        }
+        self.set_state(state)
+    }
+
     fn update_working(&mut self) -> PomoMessage<TTask> {
         // tick the timer
         if self.work_timer.is_completed() {
-            self.current_cycles += 1;
-
-            // remove first task and make it completed!
-            let completed = if !self.tasks.is_empty() {
-                let mut comp = self.tasks.remove(0);
-                comp.complete();
-                Some(comp)
-            } else {
-                None
-            };
-
-            // either long or regular break
-            let mut msg = if self.current_cycles == self.total_cycles {
-                // DONE!
-                self.set_state(PomoState::Completed)
-            } else if self.current_cycles % self.cycles_until_long_break == 0 {
-                self.skip_to(PomoState::LongBreak)
-            } else {
-                self.skip_to(PomoState::Break)
-            };
-            // if we did transition, set the completed task
-            if let PomoMessage::Transition(transition) = &mut msg {
-                transition.completed = completed;
-            }
-            msg
+            self.complete_working()
         } else {
             PomoMessage::NoMessage
         }
     }
 
+    fn complete_working(&mut self) -> PomoMessage<TTask> {
+        self.end_phase(false);
+        self.current_cycles += 1;
+
+        // remove first task and make it completed!
+        let completed = if !self.tasks.is_empty() {
+            let mut comp = self.tasks.remove(0);
+            comp.complete();
+            Some(comp)
+        } else {
+            None
+        };
+
+        // either long or regular break
+        let mut msg = if self.current_cycles == self.total_cycles {
+            // DONE!
+            self.set_state(PomoState::Completed)
+        } else if self
+            .current_cycles
+            .is_multiple_of(self.cycles_until_long_break)
+        {
+            self.enter(PomoState::LongBreak)
+        } else {
+            self.enter(PomoState::Break)
+        };
+        // if we did transition, set the completed task
+        if let PomoMessage::Transition(transition) = &mut msg {
+            transition.completed = completed;
+        }
+        msg
+    }
+
     fn update_break(&mut self) -> PomoMessage<TTask> {
         if self.break_timer.is_completed() {
-            self.skip_to(PomoState::Working)
+            self.complete_break()
         } else {
             PomoMessage::NoMessage
         }
@@ -221,17 +429,60 @@ where
 
     fn update_long_break(&mut self) -> PomoMessage<TTask> {
         if self.long_break_timer.is_completed() {
-            self.skip_to(PomoState::Working)
+            self.complete_break()
         } else {
             PomoMessage::NoMessage
         }
     }
+
+    fn complete_break(&mut self) -> PomoMessage<TTask> {
+        self.end_phase(false);
+        self.enter(PomoState::Working)
+    }
+
+    /// applies the resume policy if the active timer noticed a clock jump
+    fn check_clock_jump(&mut self) -> Option<PomoMessage<TTask>> {
+        let timer = match self.state() {
+            PomoState::Working => &mut self.work_timer,
+            PomoState::Break => &mut self.break_timer,
+            PomoState::LongBreak => &mut self.long_break_timer,
+            _ => return None,
+        };
+        let gap = timer.take_clock_jump()?;
+
+        let msg = match self.resume_policy {
+            ResumePolicy::Complete => match self.state() {
+                PomoState::Working => self.complete_working(),
+                _ => self.complete_break(),
+            },
+            ResumePolicy::Pause => {
+                timer.extend(gap);
+                // a suspend is not a pause the policy should lengthen breaks for
+                let pauses = self.pauses;
+                let msg = self.pause();
+                self.pauses = pauses;
+                msg
+            }
+            ResumePolicy::Continue => PomoMessage::NoMessage,
+        };
+
+        Some(PomoMessage::ClockJump(ClockJump {
+            gap,
+            policy: self.resume_policy,
+            transition: match msg {
+                PomoMessage::Transition(transition) => Some(transition),
+                _ => None,
+            },
+        }))
+    }
 }
 
-impl<TTask, TTimer> Actor<PomoCommand<TTask>, PomoMessage<TTask>> for SimplePomo<TTask, TTimer>
+impl<TTask, TTimer, TPolicy> Actor<PomoCommand<TTask>, PomoMessage<TTask>>
+    for SimplePomo<TTask, TTimer, TPolicy>
 where
     TTask: Task,
     TTimer: Timer,
+    TPolicy: PhasePolicy,
 {
     fn execute(&mut self, command: PomoCommand<TTask>) -> PomoMessage<TTask> {
         match command {
@@ -240,13 +491,34 @@ where
                 PomoMessage::Executed
             }
             PomoCommand::RemoveTask(index) => {
-                self.tasks.remove(index);
-                PomoMessage::Executed
+                if index < self.tasks.len() {
+                    self.tasks.remove(index);
+                    PomoMessage::Executed
+                } else {
+                    PomoMessage::NoMessage
+                }
+            }
+            PomoCommand::UpdateTask(index, task) => match self.tasks.get_mut(index) {
+                Some(existing) => {
+                    *existing = task;
+                    PomoMessage::Executed
+                }
+                None => PomoMessage::NoMessage,
+            },
+            PomoCommand::MoveTask(from, to) => {
+                if from < self.tasks.len() && to < self.tasks.len() {
+                    let task = self.tasks.remove(from);
+                    self.tasks.insert(to, task);
+                    PomoMessage::Executed
+                } else {
+                    PomoMessage::NoMessage
+                }
             }
             PomoCommand::Start => self.start(),
             PomoCommand::Pause => self.pause(),
             PomoCommand::Unpause => self.unpause(),
             PomoCommand::TogglePause => self.toggle_pause(),
+            PomoCommand::SkipTo(state) => self.skip_to(state),
             PomoCommand::Reset => self.reset(),
             PomoCommand::Update => self.update(),
             PomoCommand::Clear => self.clear(),
@@ -254,10 +526,11 @@ where
     }
 }
 
-impl<TTask, TTimer> Pomo<TTask, TTimer> for SimplePomo<TTask, TTimer>
+impl<TTask, TTimer, TPolicy> Pomo<TTask, TTimer> for SimplePomo<TTask, TTimer, TPolicy>
 where
     TTask: Task,
     TTimer: Timer,
+    TPolicy: PhasePolicy,
 {
     type PomoOut = PomoMessage<TTask>;
 
@@ -269,6 +542,7 @@ where
         self.current_cycles = 0;
         self.state = PomoState::default();
         self.prev_state = PomoState::default();
+        self.pauses = 0;
 
         PomoMessage::Reset
     }
@@ -279,12 +553,15 @@ where
     }
 
     fn update(&mut self) -> PomoMessage<TTask> {
+        if let Some(msg) = self.check_clock_jump() {
+            return msg;
+        }
+
         match self.state() {
             PomoState::NotStarted => PomoMessage::NoMessage,
             PomoState::Pending => {
                 // start the timer and change state
-                self.work_timer.reset();
-                self.set_state(PomoState::Working)
+                self.enter(PomoState::Working)
             }
             PomoState::Working => self.update_working(),
             PomoState::Break => self.update_break(),
@@ -294,31 +571,21 @@ where
     }
 }
 
-impl<TTask, TTimer> PomoActions<TTask, TTimer> for SimplePomo<TTask, TTimer>
+impl<TTask, TTimer, TPolicy> PomoActions<TTask, TTimer> for SimplePomo<TTask, TTimer, TPolicy>
 where
     TTask: Task,
     TTimer: Timer,
+    TPolicy: PhasePolicy,
 {
     type PomoActionOut = PomoMessage<TTask>;
 
     fn skip_to(&mut self, state: PomoState) -> PomoMessage<TTask> {
-        self.work_timer.reset();
-        self.break_timer.reset();
-        self.long_break_timer.reset();
-
-        match state {
-            PomoState::Working => {
-                self.work_timer.start();
-            }
-            PomoState::Break => {
-                self.break_timer.start();
-            }
-            PomoState::LongBreak => {
-                self.long_break_timer.start();
-            }
-            _ => (),
-        };
-        self.set_state(state)
+        self.end_phase(true);
+        let mut msg = self.enter(state);
+        if let PomoMessage::Transition(transition) = &mut msg {
+            transition.skipped = true;
+        }
+        msg
     }
 
     /// Should call output.state_changed!
@@ -344,6 +611,7 @@ where
                 PomoState::LongBreak => self.long_break_timer.pause(),
                 _ => (),
             }
+            self.pauses += 1;
             self.set_state(PomoState::Paused)
         } else {
             PomoMessage::Transition(Transition::new(PomoState::Paused, PomoState::Paused))
@@ -365,13 +633,14 @@ where
     }
 }
 
-impl<TTask, TTimer> PomoData<TTask, TTimer> for SimplePomo<TTask, TTimer>
+impl<TTask, TTimer, TPolicy> PomoData<TTask, TTimer> for SimplePomo<TTask, TTimer, TPolicy>
 where
     TTask: Task,
     TTimer: Timer,
+    TPolicy: PhasePolicy,
 {
     fn task(&self) -> Option<&TTask> {
-        self.tasks.get(0)
+        self.tasks.first()
     }
 
     fn tasks(&self) -> &[TTask] {
@@ -404,7 +673,7 @@ where
 mod tests {
     use std::time::Duration;
 
-    use crate::{InstantTimer, SimpleTask};
+    use crate::{AdaptivePolicy, InstantTimer, SimpleTask, SystemTimer};
 
     use super::*;
 
@@ -420,9 +689,9 @@ mod tests {
             .work_timer(InstantTimer::new(Duration::from_millis(wd - 1)))
             .long_break_timer(InstantTimer::new(Duration::from_millis(rd - 1)))
             .tasks(vec![
-                SimpleTask::new("Task1".into()),
-                SimpleTask::new("Task2".into()),
-                SimpleTask::new("Task3".into()),
+                SimpleTask::new("Task1"),
+                SimpleTask::new("Task2"),
+                SimpleTask::new("Task3"),
             ])
             .total_cycles(6_usize)
             .build()
@@ -442,13 +711,13 @@ mod tests {
             output,
             PomoMessage::Transition(Transition::new(PomoState::Pending, PomoState::Working,))
         );
-        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task1".into())));
+        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task1")));
 
         // *************
         // first update
         // *************
         let output = pomo.update();
-        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task1".into())));
+        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task1")));
         assert_eq!(output, PomoMessage::NoMessage);
 
         // *************
@@ -456,7 +725,7 @@ mod tests {
         // *************
         std::thread::sleep(Duration::from_millis(wd));
         let output = pomo.update();
-        let mut t1 = SimpleTask::new("Task1".into());
+        let mut t1 = SimpleTask::new("Task1");
         // task completed call
         t1.complete();
         // transition
@@ -502,7 +771,7 @@ mod tests {
         assert!(!pomo.long_break_timer.is_paused());
 
         let output = pomo.update();
-        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task2".into())));
+        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task2")));
         assert_eq!(output, PomoMessage::NoMessage);
 
         // *************
@@ -511,7 +780,7 @@ mod tests {
         std::thread::sleep(Duration::from_millis(bd));
 
         let output = pomo.update();
-        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task2".into())));
+        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task2")));
         // transition
         assert_eq!(
             output,
@@ -524,9 +793,9 @@ mod tests {
         std::thread::sleep(Duration::from_millis(wd));
 
         let output = pomo.update();
-        let mut t1 = SimpleTask::new("Task2".into());
+        let mut t1 = SimpleTask::new("Task2");
         t1.complete();
-        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task3".into())));
+        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task3")));
         // transition
         assert_eq!(
             output,
@@ -543,7 +812,7 @@ mod tests {
         std::thread::sleep(Duration::from_millis(bd));
 
         let output = pomo.update();
-        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task3".into())));
+        assert_eq!(pomo.task(), Some(&SimpleTask::new("Task3")));
         // transition
         assert_eq!(
             output,
@@ -558,7 +827,7 @@ mod tests {
         let output = pomo.update();
         assert_eq!(pomo.task(), None);
         // transition
-        let mut t1 = SimpleTask::new("Task3".into());
+        let mut t1 = SimpleTask::new("Task3");
         t1.complete();
         assert_eq!(
             output,
@@ -695,5 +964,235 @@ mod tests {
             pomo.tasks,
             vec![SimpleTask::new("Test1"), SimpleTask::new("Test3")]
         );
+        assert_eq!(
+            pomo.execute(PomoCommand::RemoveTask(2)),
+            PomoMessage::NoMessage
+        );
+        assert_eq!(pomo.tasks.len(), 2);
+    }
+
+    #[test]
+    fn it_should_update_and_move_tasks() {
+        let mut pomo = SimplePomo::<SimpleTask, InstantTimer>::default();
+        for task in ["Test1", "Test2", "Test3"] {
+            pomo.execute(PomoCommand::AddTask(SimpleTask::new(task)));
+        }
+
+        assert_eq!(
+            pomo.execute(PomoCommand::UpdateTask(1, SimpleTask::new("Edited"))),
+            PomoMessage::Executed
+        );
+        assert_eq!(
+            pomo.execute(PomoCommand::MoveTask(2, 0)),
+            PomoMessage::Executed
+        );
+        assert_eq!(
+            pomo.tasks,
+            vec![
+                SimpleTask::new("Test3"),
+                SimpleTask::new("Test1"),
+                SimpleTask::new("Edited")
+            ]
+        );
+        assert_eq!(pomo.task(), Some(&SimpleTask::new("Test3")));
+
+        assert_eq!(
+            pomo.execute(PomoCommand::MoveTask(0, 3)),
+            PomoMessage::NoMessage
+        );
+        assert_eq!(
+            pomo.execute(PomoCommand::UpdateTask(3, SimpleTask::new("Nope"))),
+            PomoMessage::NoMessage
+        );
+    }
+
+    #[test]
+    fn it_should_consult_the_phase_policy() {
+        let mut pomo = SimplePomoBuilder::<SimpleTask, InstantTimer, AdaptivePolicy>::default()
+            .work_timer(InstantTimer::new(Duration::from_millis(50)))
+            .break_timer(InstantTimer::new(Duration::from_secs(60 * 5)))
+            .build()
+            .unwrap();
+
+        pomo.start();
+        pomo.update();
+        pomo.pause();
+        pomo.unpause();
+        assert_eq!(pomo.pauses, 1);
+
+        // a paused work phase leads to a longer break
+        std::thread::sleep(Duration::from_millis(60));
+        pomo.update();
+        assert_eq!(pomo.state(), PomoState::Break);
+        assert!(pomo.break_timer.goal() >= Duration::from_secs(60 * 6));
+        assert!(pomo.break_timer.goal() < Duration::from_secs(60 * 6 + 1));
+
+        // a skipped break leads to shorter work, but never below the minimum
+        pomo.skip_to(PomoState::Working);
+        assert_eq!(pomo.work_timer.goal(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn it_should_shorten_work_after_skipped_break() {
+        let mut pomo = SimplePomoBuilder::<SimpleTask, InstantTimer, AdaptivePolicy>::default()
+            .build()
+            .unwrap();
+
+        pomo.start();
+        pomo.update();
+        pomo.skip_to(PomoState::Break);
+        assert_eq!(pomo.break_timer.goal(), Duration::from_secs(60 * 5));
+        pomo.skip_to(PomoState::Working);
+        assert_eq!(pomo.work_timer.goal(), Duration::from_secs(60 * 20));
+    }
+
+    #[test]
+    fn it_should_use_the_next_work_goal_once() {
+        let mut pomo = SimplePomoBuilder::<SimpleTask, InstantTimer>::default()
+            .next_work_goal(Goal::Duration(Duration::from_secs(60 * 40)))
+            .build()
+            .unwrap();
+
+        pomo.start();
+        pomo.update();
+        assert_eq!(pomo.state(), PomoState::Working);
+        assert_eq!(pomo.work_timer.goal(), Duration::from_secs(60 * 40));
+        assert_eq!(pomo.next_work_goal, None);
+
+        pomo.skip_to(PomoState::Break);
+        pomo.skip_to(PomoState::Working);
+        assert_eq!(pomo.work_timer.goal(), Duration::from_secs(60 * 25));
+    }
+
+    #[test]
+    fn it_should_mark_skipped_transitions() {
+        let mut pomo = SimplePomo::<SimpleTask, InstantTimer>::default();
+        pomo.start();
+        pomo.update();
+
+        let mut expected = Transition::new(PomoState::Working, PomoState::Break);
+        expected.skipped = true;
+        assert_eq!(
+            pomo.skip_to(PomoState::Break),
+            PomoMessage::Transition(expected)
+        );
+    }
+
+    #[test]
+    fn it_should_fast_forward() {
+        let mut pomo = SimplePomoBuilder::<SimpleTask, InstantTimer>::default()
+            .tasks(vec![SimpleTask::new("Task1"), SimpleTask::new("Task2")])
+            .build()
+            .unwrap();
+        pomo.start();
+
+        // work 25m, break 5m and 3m into the next work phase
+        let messages = pomo.fast_forward(Duration::from_secs(60 * 33));
+        assert_eq!(messages.len(), 3);
+        assert_eq!(pomo.state(), PomoState::Working);
+        assert_eq!(pomo.current_cycles, 1);
+        assert_eq!(pomo.tasks, vec![SimpleTask::new("Task2")]);
+        let elapsed = pomo.work_timer.elapsed().unwrap();
+        assert!(elapsed >= Duration::from_secs(60 * 3));
+        assert!(elapsed < Duration::from_secs(60 * 3 + 1));
+
+        pomo.pause();
+        assert!(pomo.fast_forward(Duration::from_secs(60 * 60)).is_empty());
+        assert_eq!(pomo.state(), PomoState::Paused);
+    }
+
+    #[test]
+    fn it_should_mirror_snapshots() {
+        let mut pomo = SimplePomoBuilder::<SimpleTask, InstantTimer>::default()
+            .tasks(vec![SimpleTask::new("Task1"), SimpleTask::new("Task2")])
+            .build()
+            .unwrap();
+        pomo.start();
+        pomo.update();
+        pomo.pause();
+
+        let mut mirror = SimplePomo::<SimpleTask, InstantTimer>::default();
+        assert_eq!(
+            mirror.apply_snapshot(pomo.snapshot()),
+            PomoMessage::Transition(Transition::new(PomoState::NotStarted, PomoState::Paused))
+        );
+        assert!(mirror.work_timer.is_paused());
+        assert_eq!(mirror.tasks, pomo.tasks);
+        assert_eq!(
+            mirror.apply_snapshot(pomo.snapshot()),
+            PomoMessage::NoMessage
+        );
+
+        pomo.unpause();
+        pomo.skip_to(PomoState::Break);
+        pomo.current_cycles += 1;
+        pomo.tasks.remove(0);
+
+        let mut completed = SimpleTask::new("Task1");
+        completed.complete();
+        assert_eq!(
+            mirror.apply_snapshot(pomo.snapshot()),
+            PomoMessage::Transition(Transition::new_task(
+                PomoState::Paused,
+                PomoState::Break,
+                completed
+            ))
+        );
+        assert_eq!(mirror.current_cycles, 1);
+        assert_eq!(
+            mirror.snapshot().timer.map(|timer| timer.goal),
+            Some(Duration::from_secs(60 * 5))
+        );
+    }
+
+    #[test]
+    fn it_should_apply_resume_policy() {
+        let mut pomo = SimplePomoBuilder::<SimpleTask, SystemTimer>::default()
+            .tasks(vec![SimpleTask::new("Task1")])
+            .resume_policy(ResumePolicy::Pause)
+            .build()
+            .unwrap();
+        pomo.start();
+        pomo.update();
+
+        pomo.work_timer.simulate_suspend(Duration::from_secs(60));
+        assert_eq!(
+            pomo.update(),
+            PomoMessage::ClockJump(ClockJump {
+                gap: pomo.work_timer.goal() - Duration::from_secs(60 * 25),
+                policy: ResumePolicy::Pause,
+                transition: Some(Transition::new(PomoState::Working, PomoState::Paused)),
+            })
+        );
+        assert!(pomo.is_paused());
+        assert_eq!(pomo.pauses, 0);
+
+        pomo.resume_policy = ResumePolicy::Complete;
+        pomo.unpause();
+        pomo.work_timer.simulate_suspend(Duration::from_secs(60));
+        let mut task = SimpleTask::new("Task1");
+        task.complete();
+        match pomo.update() {
+            PomoMessage::ClockJump(jump) => assert_eq!(
+                jump.transition,
+                Some(Transition::new_task(
+                    PomoState::Working,
+                    PomoState::Break,
+                    task
+                ))
+            ),
+            msg => panic!("Unexpected message {}", msg),
+        }
+
+        pomo.resume_policy = ResumePolicy::Continue;
+        pomo.break_timer
+            .simulate_suspend(Duration::from_secs(60 * 10));
+        assert!(matches!(pomo.update(), PomoMessage::ClockJump(_)));
+        assert_eq!(pomo.state(), PomoState::Break);
+        // the suspended time counted, the break is over
+        assert_eq!(
+            pomo.update(),
+            PomoMessage::Transition(Transition::new(PomoState::Break, PomoState::Working))
+        );
     }
 }
@@ -0,0 +1,159 @@
+use crate::{Settings, StoreError};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The current version of the preset exchange format
+pub const PRESETS_VERSION: u64 = 1;
+
+/// Named settings that can be switched to at once
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: Settings,
+}
+
+/// The document presets are exchanged in between frontends
+#[derive(Serialize, Deserialize)]
+struct PresetFile {
+    version: u64,
+    presets: Vec<Preset>,
+}
+
+impl Preset {
+    pub fn new(name: &str, settings: Settings) -> Self {
+        Self {
+            name: name.into(),
+            settings,
+        }
+    }
+
+    /// the presets every frontend offers
+    pub fn builtin() -> Vec<Self> {
+        let preset = |name, work: u64, short_break: u64, long_break: u64| {
+            Self::new(
+                name,
+                Settings {
+                    work_time: Duration::from_secs(60 * work),
+                    short_break_time: Duration::from_secs(60 * short_break),
+                    long_break_time: Duration::from_secs(60 * long_break),
+                    ..Default::default()
+                },
+            )
+        };
+        vec![
+            preset("classic", 25, 5, 15),
+            preset("deep work", 50, 10, 30),
+            preset("short sprints", 15, 3, 10),
+        ]
+    }
+
+    /// Adds presets to a list,
+    /// presets with the same name are replaced
+    pub fn merge(presets: &mut Vec<Self>, other: Vec<Self>) {
+        for preset in other {
+            match presets.iter_mut().find(|p| p.name == preset.name) {
+                Some(existing) => *existing = preset,
+                None => presets.push(preset),
+            }
+        }
+    }
+
+    /// writes presets in the exchange format
+    pub fn export(presets: &[Self]) -> Result<String, StoreError> {
+        let file = PresetFile {
+            version: PRESETS_VERSION,
+            presets: presets.to_vec(),
+        };
+        Ok(serde_json::to_string_pretty(&file)?)
+    }
+
+    /// reads presets in the exchange format,
+    /// presets that could not run a timer are rejected
+    pub fn import(input: &str) -> Result<Vec<Self>, StoreError> {
+        let file: PresetFile = serde_json::from_str(input)?;
+        if file.version > PRESETS_VERSION {
+            return Err(StoreError::UnsupportedVersion(file.version));
+        }
+        for preset in &file.presets {
+            preset.validate()?;
+        }
+        Ok(file.presets)
+    }
+
+    fn validate(&self) -> Result<(), StoreError> {
+        let settings = &self.settings;
+        let invalid = if settings.work_time.is_zero() {
+            Some("work time")
+        } else if settings.short_break_time.is_zero() {
+            Some("short break time")
+        } else if settings.long_break_time.is_zero() {
+            Some("long break time")
+        } else if settings.cycles_until_long_break == 0 {
+            Some("cycles until long break")
+        } else if settings.total_cycles == 0 {
+            Some("total cycles")
+        } else {
+            None
+        };
+
+        match invalid {
+            Some(field) => Err(StoreError::Format(format!(
+                "{} of preset '{}' must not be zero",
+                field, self.name
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_import_exported_presets() {
+        let presets = Preset::builtin();
+        let output = Preset::export(&presets).unwrap();
+        assert!(output.contains("\"work_time\": \"50m\""));
+        assert_eq!(Preset::import(&output), Ok(presets));
+
+        assert_eq!(
+            Preset::import("{\"version\": 2, \"presets\": []}"),
+            Err(StoreError::UnsupportedVersion(2))
+        );
+    }
+
+    #[test]
+    fn it_should_reject_invalid_presets() {
+        let mut preset = Preset::new("broken", Settings::default());
+        preset.settings.work_time = Duration::ZERO;
+        let output = Preset::export(&[preset.clone()]).unwrap();
+        assert_eq!(
+            Preset::import(&output),
+            Err(StoreError::Format(
+                "work time of preset 'broken' must not be zero".into()
+            ))
+        );
+
+        preset.settings = Settings::default();
+        preset.settings.total_cycles = 0;
+        let output = Preset::export(&[preset]).unwrap();
+        assert!(Preset::import(&output).is_err());
+    }
+
+    #[test]
+    fn it_should_merge_presets() {
+        let mut presets = Preset::builtin();
+        let mut classic = Preset::new("classic", Settings::default());
+        classic.settings.total_cycles = 2;
+
+        Preset::merge(
+            &mut presets,
+            vec![classic.clone(), Preset::new("custom", Settings::default())],
+        );
+        assert_eq!(presets.len(), 4);
+        assert_eq!(presets[0], classic);
+        assert_eq!(presets[3].name, "custom");
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{Actor, PomoCommand, PomoData, PomoMessage, Task, Timer};
+use std::marker::PhantomData;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// Drives a pomo machine asynchronously.
+/// Instead of polling the runner sleeps until the active timer
+/// reaches its goal or until a command arrives.
+/// Every message other than NoMessage is emitted on the message stream.
+pub struct PomoRunner<TTask, TTimer, TPomo>
+where
+    TTask: Task,
+    TTimer: Timer,
+{
+    pomo: TPomo,
+    commands: mpsc::UnboundedReceiver<PomoCommand<TTask>>,
+    messages: mpsc::UnboundedSender<PomoMessage<TTask>>,
+    _timer: PhantomData<TTimer>,
+}
+
+/// Sends commands to a running pomo runner
+pub type PomoSender<TTask> = mpsc::UnboundedSender<PomoCommand<TTask>>;
+
+/// Messages emitted by a pomo runner
+pub type PomoStream<TTask> = UnboundedReceiverStream<PomoMessage<TTask>>;
+
+impl<TTask, TTimer, TPomo> PomoRunner<TTask, TTimer, TPomo>
+where
+    TTask: Task,
+    TTimer: Timer,
+    TPomo: Actor<PomoCommand<TTask>, PomoMessage<TTask>> + PomoData<TTask, TTimer>,
+{
+    /// Creates a runner, a command sender and a message stream
+    pub fn new(pomo: TPomo) -> (Self, PomoSender<TTask>, PomoStream<TTask>) {
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (message_tx, message_rx) = mpsc::unbounded_channel();
+
+        (
+            Self {
+                pomo,
+                commands: command_rx,
+                messages: message_tx,
+                _timer: PhantomData,
+            },
+            command_tx,
+            UnboundedReceiverStream::new(message_rx),
+        )
+    }
+
+    /// Runs until all command senders or the message stream are dropped
+    /// and returns the pomo machine
+    pub async fn run(self) -> TPomo {
+        self.run_with(|_, _| ()).await
+    }
+
+    /// Like run, but calls inspect with the machine
+    /// for every message before it is emitted
+    pub async fn run_with<F>(mut self, mut inspect: F) -> TPomo
+    where
+        F: FnMut(&TPomo, &PomoMessage<TTask>),
+    {
+        loop {
+            let message = tokio::select! {
+                command = self.commands.recv() => match command {
+                    Some(command) => self.pomo.execute(command),
+                    None => break,
+                },
+                _ = Self::sleep(self.pomo.next_update()) => {
+                    self.pomo.execute(PomoCommand::Update)
+                },
+            };
+
+            if matches!(message, PomoMessage::NoMessage) {
+                continue;
+            }
+
+            inspect(&self.pomo, &message);
+            if self.messages.send(message).is_err() {
+                break;
+            }
+        }
+        self.pomo
+    }
+
+    async fn sleep(duration: Option<Duration>) {
+        match duration {
+            Some(duration) => tokio::time::sleep(duration).await,
+            None => std::future::pending().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InstantTimer, PomoState, SimplePomo, SimplePomoBuilder, SimpleTask, Transition};
+    use tokio_stream::StreamExt;
+
+    /// counts the updates the runner executes while the pomo is paused
+    struct CountingPomo {
+        pomo: SimplePomo<SimpleTask, InstantTimer>,
+        paused_updates: usize,
+    }
+
+    impl Actor<PomoCommand<SimpleTask>, PomoMessage<SimpleTask>> for CountingPomo {
+        fn execute(&mut self, command: PomoCommand<SimpleTask>) -> PomoMessage<SimpleTask> {
+            if matches!(command, PomoCommand::Update) && self.pomo.is_paused() {
+                self.paused_updates += 1;
+            }
+            self.pomo.execute(command)
+        }
+    }
+
+    impl PomoData<SimpleTask, InstantTimer> for CountingPomo {
+        fn state(&self) -> PomoState {
+            self.pomo.state()
+        }
+
+        fn timer(&self) -> Option<&InstantTimer> {
+            self.pomo.timer()
+        }
+
+        fn task(&self) -> Option<&SimpleTask> {
+            self.pomo.task()
+        }
+
+        fn tasks(&self) -> &[SimpleTask] {
+            self.pomo.tasks()
+        }
+
+        fn tasks_mut(&mut self) -> &mut [SimpleTask] {
+            self.pomo.tasks_mut()
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_transition_at_deadlines() {
+        let pomo = SimplePomoBuilder::<SimpleTask, InstantTimer>::default()
+            .work_timer(InstantTimer::new(Duration::from_millis(50)))
+            .break_timer(InstantTimer::new(Duration::from_millis(50)))
+            .total_cycles(2_usize)
+            .build()
+            .unwrap();
+
+        let (runner, commands, mut messages) = PomoRunner::new(pomo);
+        let handle = tokio::spawn(runner.run());
+
+        commands.send(PomoCommand::Start).unwrap();
+
+        let mut transitions = vec![];
+        while let Some(message) = messages.next().await {
+            if let PomoMessage::Transition(transition) = message {
+                let done = transition.to == PomoState::Completed;
+                transitions.push(transition);
+                if done {
+                    break;
+                }
+            }
+        }
+
+        assert_eq!(
+            transitions,
+            vec![
+                Transition::new(PomoState::NotStarted, PomoState::Pending),
+                Transition::new(PomoState::Pending, PomoState::Working),
+                Transition::new(PomoState::Working, PomoState::Break),
+                Transition::new(PomoState::Break, PomoState::Working),
+                Transition::new(PomoState::Working, PomoState::Completed),
+            ]
+        );
+
+        drop(commands);
+        let pomo = handle.await.unwrap();
+        assert!(pomo.is_completed());
+    }
+
+    #[tokio::test]
+    async fn it_should_not_wake_while_paused() {
+        let pomo = CountingPomo {
+            pomo: SimplePomoBuilder::<SimpleTask, InstantTimer>::default()
+                .work_timer(InstantTimer::new(Duration::from_millis(20)))
+                .build()
+                .unwrap(),
+            paused_updates: 0,
+        };
+
+        let (runner, commands, mut messages) = PomoRunner::new(pomo);
+        let handle = tokio::spawn(runner.run());
+
+        commands.send(PomoCommand::Start).unwrap();
+        commands.send(PomoCommand::Update).unwrap();
+        commands.send(PomoCommand::Pause).unwrap();
+        for _ in 0..3 {
+            messages.next().await;
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(commands);
+        let pomo = handle.await.unwrap();
+        assert!(pomo.is_paused());
+        assert_eq!(pomo.paused_updates, 0);
+        assert_eq!(messages.next().await, None);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{serde_duration, PomoState};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The state of a running timer
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerSnapshot {
+    /// time the timer ran, not counting the current pause
+    #[serde(with = "serde_duration")]
+    pub elapsed: Duration,
+    #[serde(with = "serde_duration")]
+    pub goal: Duration,
+    pub paused: bool,
+}
+
+/// Everything needed to mirror a pomo machine elsewhere,
+/// e.g. on the clients of a shared session
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PomoSnapshot<TTask> {
+    pub state: PomoState,
+    pub prev_state: PomoState,
+    pub tasks: Vec<TTask>,
+    pub current_cycles: usize,
+    pub cycles_until_long_break: usize,
+    pub total_cycles: usize,
+    /// the timer of the active phase
+    pub timer: Option<TimerSnapshot>,
+}
@@ -0,0 +1,215 @@
+use crate::{HistoryEntry, PomoState};
+use chrono::{Datelike, Days, NaiveDate, TimeZone};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Focus time and completed pomodoros of a day or week
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeriodStats {
+    /// the day or the monday of the week
+    pub start: NaiveDate,
+    pub focus: Duration,
+    pub pomodoros: usize,
+}
+
+/// Statistics of a recorded history
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// the last days up to today, oldest first
+    pub days: Vec<PeriodStats>,
+    /// the last weeks up to this week, oldest first
+    pub weeks: Vec<PeriodStats>,
+    /// completed pomodoros per task, most first
+    pub tasks: Vec<(String, usize)>,
+    /// days in a row with a completed pomodoro up to today,
+    /// today only breaks the streak once it is over
+    pub streak: usize,
+    pub longest_streak: usize,
+}
+
+impl Stats {
+    /// Collects the stats of the last days and weeks up to today.
+    /// Entries are assigned to the day they started on in tz
+    pub fn new<Tz: TimeZone>(
+        entries: &[HistoryEntry],
+        today: NaiveDate,
+        tz: &Tz,
+        days: usize,
+        weeks: usize,
+    ) -> Self {
+        let mut focus: HashMap<NaiveDate, Duration> = HashMap::new();
+        let mut pomodoros: HashMap<NaiveDate, usize> = HashMap::new();
+        let mut tasks: HashMap<String, usize> = HashMap::new();
+
+        for entry in entries {
+            if entry.state != PomoState::Working {
+                continue;
+            }
+            let day = entry.start.with_timezone(tz).date_naive();
+            *focus.entry(day).or_default() += entry.duration().to_std().unwrap_or_default();
+
+            // pauses split a phase into several entries, only the last one completes it
+            if entry.completed {
+                *pomodoros.entry(day).or_default() += 1;
+                if let Some(task) = &entry.task {
+                    *tasks.entry(task.clone()).or_default() += 1;
+                }
+            }
+        }
+
+        let period = |start: NaiveDate, len: u64| {
+            let mut stats = PeriodStats {
+                start,
+                focus: Duration::default(),
+                pomodoros: 0,
+            };
+            for day in (0..len).filter_map(|i| start.checked_add_days(Days::new(i))) {
+                stats.focus += focus.get(&day).copied().unwrap_or_default();
+                stats.pomodoros += pomodoros.get(&day).copied().unwrap_or_default();
+            }
+            stats
+        };
+
+        let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+        let mut tasks = tasks.into_iter().collect::<Vec<_>>();
+        tasks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            days: (0..days as u64)
+                .rev()
+                .filter_map(|i| today.checked_sub_days(Days::new(i)))
+                .map(|day| period(day, 1))
+                .collect(),
+            weeks: (0..weeks as u64)
+                .rev()
+                .filter_map(|i| monday.checked_sub_days(Days::new(i * 7)))
+                .map(|week| period(week, 7))
+                .collect(),
+            tasks,
+            streak: Self::streak(&pomodoros, today),
+            longest_streak: Self::longest_streak(&pomodoros),
+        }
+    }
+
+    fn streak(pomodoros: &HashMap<NaiveDate, usize>, today: NaiveDate) -> usize {
+        let start = if pomodoros.contains_key(&today) {
+            Some(today)
+        } else {
+            today.pred_opt()
+        };
+        std::iter::successors(start, |day| day.pred_opt())
+            .take_while(|day| pomodoros.contains_key(day))
+            .count()
+    }
+
+    fn longest_streak(pomodoros: &HashMap<NaiveDate, usize>) -> usize {
+        let mut days = pomodoros.keys().copied().collect::<Vec<_>>();
+        days.sort();
+
+        let mut longest = 0;
+        let mut current = 0;
+        let mut prev: Option<NaiveDate> = None;
+        for day in days {
+            current = match prev {
+                Some(prev) if prev.succ_opt() == Some(day) => current + 1,
+                _ => 1,
+            };
+            longest = longest.max(current);
+            prev = Some(day);
+        }
+        longest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    fn entry(
+        state: PomoState,
+        start: &str,
+        minutes: i64,
+        task: Option<&str>,
+        completed: bool,
+    ) -> HistoryEntry {
+        let start = start.parse::<DateTime<Utc>>().unwrap();
+        HistoryEntry {
+            state,
+            start,
+            end: start + chrono::Duration::minutes(minutes),
+            task: task.map(|task| task.into()),
+            skipped: false,
+            completed,
+        }
+    }
+
+    #[test]
+    fn it_should_collect_stats() {
+        let entries = vec![
+            // wednesday
+            entry(
+                PomoState::Working,
+                "2021-12-01T09:00:00Z",
+                25,
+                Some("A"),
+                true,
+            ),
+            entry(PomoState::Break, "2021-12-01T09:25:00Z", 5, None, true),
+            // thursday, paused once
+            entry(PomoState::Working, "2021-12-02T09:00:00Z", 10, None, false),
+            entry(PomoState::Paused, "2021-12-02T09:10:00Z", 5, None, false),
+            entry(PomoState::Working, "2021-12-02T09:15:00Z", 15, None, true),
+            entry(PomoState::Break, "2021-12-02T09:30:00Z", 5, None, true),
+            // skipped
+            entry(PomoState::Working, "2021-12-02T10:00:00Z", 5, None, false),
+            // friday
+            entry(
+                PomoState::Working,
+                "2021-12-03T09:00:00Z",
+                25,
+                Some("A"),
+                true,
+            ),
+            entry(
+                PomoState::Working,
+                "2021-12-03T10:00:00Z",
+                25,
+                Some("B"),
+                true,
+            ),
+            // monday
+            entry(
+                PomoState::Working,
+                "2021-12-06T09:00:00Z",
+                25,
+                Some("B"),
+                true,
+            ),
+        ];
+        let today = NaiveDate::from_ymd_opt(2021, 12, 7).unwrap();
+        let stats = Stats::new(&entries, today, &Utc, 7, 2);
+
+        assert_eq!(stats.days.len(), 7);
+        assert_eq!(
+            stats.days[0].start,
+            NaiveDate::from_ymd_opt(2021, 12, 1).unwrap()
+        );
+        assert_eq!(stats.days[1].focus, Duration::from_secs(60 * 30));
+        assert_eq!(stats.days[1].pomodoros, 1);
+        assert_eq!(stats.days[2].pomodoros, 2);
+        assert_eq!(stats.days[6].pomodoros, 0);
+
+        assert_eq!(stats.weeks.len(), 2);
+        assert_eq!(
+            stats.weeks[0].start,
+            NaiveDate::from_ymd_opt(2021, 11, 29).unwrap()
+        );
+        assert_eq!(stats.weeks[0].pomodoros, 4);
+        assert_eq!(stats.weeks[1].pomodoros, 1);
+
+        assert_eq!(stats.tasks, vec![("A".into(), 2), ("B".into(), 2)]);
+        assert_eq!(stats.streak, 1);
+        assert_eq!(stats.longest_streak, 3);
+    }
+}
@@ -0,0 +1,487 @@
+use crate::{
+    serde_duration, HistoryEntry, PhasePolicy, PomoMessage, PomoSnapshot, Preset, SimplePomo,
+    SimpleTask, Task, Timer,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The current version of the stored document
+pub const STORE_VERSION: u64 = 2;
+
+/// Settings shared by all frontends
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(with = "serde_duration")]
+    pub work_time: Duration,
+    #[serde(with = "serde_duration")]
+    pub short_break_time: Duration,
+    #[serde(with = "serde_duration")]
+    pub long_break_time: Duration,
+    pub cycles_until_long_break: usize,
+    pub total_cycles: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            work_time: Duration::from_secs(60 * 25),
+            short_break_time: Duration::from_secs(60 * 5),
+            long_break_time: Duration::from_secs(60 * 30),
+            cycles_until_long_break: 4,
+            total_cycles: 8,
+        }
+    }
+}
+
+impl Settings {
+    /// replaces the timers and cycle counts of a pomo machine
+    pub fn apply<TTask, TTimer, TPolicy>(&self, pomo: &mut SimplePomo<TTask, TTimer, TPolicy>)
+    where
+        TTask: Task,
+        TTimer: Timer,
+        TPolicy: PhasePolicy,
+    {
+        pomo.work_timer = TTimer::from_goal(self.work_time);
+        pomo.break_timer = TTimer::from_goal(self.short_break_time);
+        pomo.long_break_timer = TTimer::from_goal(self.long_break_time);
+        pomo.cycles_until_long_break = self.cycles_until_long_break;
+        pomo.total_cycles = self.total_cycles;
+    }
+}
+
+/// A running session, saved so it survives restarts
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSession {
+    pub snapshot: PomoSnapshot<SimpleTask>,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl SavedSession {
+    pub fn new(snapshot: PomoSnapshot<SimpleTask>, saved_at: DateTime<Utc>) -> Self {
+        Self { snapshot, saved_at }
+    }
+
+    /// Makes a pomo machine continue the session at now.
+    /// Returns the transition into the saved state
+    /// and the transitions of the phases that ended since it was saved
+    pub fn restore<TTimer, TPolicy>(
+        self,
+        pomo: &mut SimplePomo<SimpleTask, TTimer, TPolicy>,
+        now: DateTime<Utc>,
+    ) -> Vec<PomoMessage<SimpleTask>>
+    where
+        TTimer: Timer,
+        TPolicy: PhasePolicy,
+    {
+        let gap = (now - self.saved_at).to_std().unwrap_or_default();
+        let mut messages = match pomo.apply_snapshot(self.snapshot) {
+            PomoMessage::NoMessage => vec![],
+            message => vec![message],
+        };
+        messages.extend(pomo.fast_forward(gap));
+        messages
+    }
+}
+
+/// Everything a frontend persists
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreData {
+    pub tasks: Vec<SimpleTask>,
+    pub settings: Settings,
+    pub history: Vec<HistoryEntry>,
+    /// the running session, if any
+    #[serde(default)]
+    pub session: Option<SavedSession>,
+    /// presets saved or imported by the user
+    #[serde(default)]
+    pub presets: Vec<Preset>,
+}
+
+impl StoreData {
+    /// the tasks a pomo machine should work on
+    pub fn pending_tasks(&self) -> Vec<SimpleTask> {
+        self.tasks
+            .iter()
+            .filter(|task| !task.is_completed())
+            .cloned()
+            .collect()
+    }
+
+    /// the builtin presets and the ones of the user,
+    /// the user's replace builtins of the same name
+    pub fn presets(&self) -> Vec<Preset> {
+        let mut presets = Preset::builtin();
+        Preset::merge(&mut presets, self.presets.clone());
+        presets
+    }
+
+    /// replaces the pending tasks, completed tasks are kept
+    pub fn set_pending_tasks(&mut self, tasks: &[SimpleTask]) {
+        self.tasks.retain(|task| task.is_completed());
+        self.tasks.extend_from_slice(tasks);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StoreError {
+    Read(String),
+    Write(String),
+    Format(String),
+    /// the document was written by a newer version
+    UnsupportedVersion(u64),
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Read(err) => write!(f, "Unable to read store: {}", err),
+            Self::Write(err) => write!(f, "Unable to write store: {}", err),
+            Self::Format(err) => write!(f, "Invalid store: {}", err),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported store version {}", version)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<serde_json::Error> for StoreError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Format(err.to_string())
+    }
+}
+
+/// A store persists a single versioned json document.
+/// Implementations only have to read and write the raw document,
+/// loading migrates documents of older versions.
+pub trait Store {
+    /// returns the stored document or None if nothing was stored yet
+    fn read(&self) -> Result<Option<String>, StoreError>;
+
+    fn write(&mut self, document: &str) -> Result<(), StoreError>;
+
+    fn load(&self) -> Result<StoreData, StoreError> {
+        match self.read()? {
+            Some(document) => {
+                let value = migrate(serde_json::from_str(&document)?)?;
+                Ok(serde_json::from_value(value)?)
+            }
+            None => Ok(StoreData::default()),
+        }
+    }
+
+    fn save(&mut self, data: &StoreData) -> Result<(), StoreError> {
+        let mut value = serde_json::to_value(data)?;
+        value["version"] = json!(STORE_VERSION);
+        self.write(&serde_json::to_string_pretty(&value)?)
+    }
+}
+
+/// Migrations from each version to the next
+/// the index is the version a migration starts from
+const MIGRATIONS: &[fn(Value) -> Value] = &[migrate_v0, migrate_v1];
+
+/// Upgrades a document to the current version
+/// Documents without version are version 0
+pub fn migrate(mut value: Value) -> Result<Value, StoreError> {
+    let version = value["version"].as_u64().unwrap_or(0);
+    if version > STORE_VERSION {
+        return Err(StoreError::UnsupportedVersion(version));
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        value = migration(value);
+    }
+    value["version"] = json!(STORE_VERSION);
+    Ok(value)
+}
+
+/// Version 0 is the flat layout the web ui used to store,
+/// times are minutes and tasks are plain descriptions
+fn migrate_v0(value: Value) -> Value {
+    let defaults = Settings::default();
+
+    let minutes = |key: &str, default: Duration| match &value[key] {
+        Value::String(mins) if mins.trim().parse::<f64>().is_ok() => {
+            format!("{}m", mins.trim())
+        }
+        _ => crate::TimeParser::format(default),
+    };
+    let count = |key: &str, default: usize| match &value[key] {
+        Value::String(count) => count.trim().parse::<usize>().unwrap_or(default),
+        Value::Number(count) => count.as_u64().map_or(default, |x| x as usize),
+        _ => default,
+    };
+
+    let tasks = match &value["tasks"] {
+        Value::Array(tasks) => tasks
+            .iter()
+            .filter_map(|task| task.as_str())
+            .map(|task| json!({ "description": task, "completed": false }))
+            .collect(),
+        _ => vec![],
+    };
+
+    json!({
+        "tasks": tasks,
+        "settings": {
+            "work_time": minutes("work_time", defaults.work_time),
+            "short_break_time": minutes("short_break_time", defaults.short_break_time),
+            "long_break_time": minutes("long_break_time", defaults.long_break_time),
+            "cycles_until_long_break":
+                count("cycles_until_long_break", defaults.cycles_until_long_break),
+            "total_cycles": count("total_cycles", defaults.total_cycles),
+        },
+        "history": [],
+    })
+}
+
+/// Version 1 did not record whether a phase completed,
+/// a phase completed unless it was skipped or paused
+fn migrate_v1(mut value: Value) -> Value {
+    if let Some(history) = value["history"].as_array_mut() {
+        let paused = |entry: &Value| entry["state"] == "Paused";
+        let completed = (0..history.len())
+            .map(|i| {
+                let entry = &history[i];
+                !paused(entry)
+                    && entry["skipped"] != true
+                    && (!entry["task"].is_null()
+                        || history.get(i + 1).is_some_and(|next| !paused(next)))
+            })
+            .collect::<Vec<_>>();
+        for (entry, completed) in history.iter_mut().zip(completed) {
+            entry["completed"] = json!(completed);
+        }
+    }
+    value
+}
+
+/// Stores the document in a json file
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl Store for JsonFileStore {
+    fn read(&self) -> Result<Option<String>, StoreError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(document) => Ok(Some(document)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(StoreError::Read(err.to_string())),
+        }
+    }
+
+    fn write(&mut self, document: &str) -> Result<(), StoreError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| StoreError::Write(err.to_string()))?;
+        }
+        std::fs::write(&self.path, document).map_err(|err| StoreError::Write(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InstantTimer, Pomo, PomoData, PomoState, Transition};
+
+    #[derive(Default)]
+    struct MemoryStore {
+        document: Option<String>,
+    }
+
+    impl Store for MemoryStore {
+        fn read(&self) -> Result<Option<String>, StoreError> {
+            Ok(self.document.clone())
+        }
+
+        fn write(&mut self, document: &str) -> Result<(), StoreError> {
+            self.document = Some(document.into());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn it_should_load_defaults() {
+        let store = MemoryStore::default();
+        assert_eq!(store.load(), Ok(StoreData::default()));
+    }
+
+    #[test]
+    fn it_should_save_and_load() {
+        let mut store = MemoryStore::default();
+        let mut data = StoreData::default();
+        data.tasks.push(SimpleTask::new("Task1"));
+        data.settings.work_time = Duration::from_secs(60 * 50);
+
+        store.save(&data).unwrap();
+        assert!(store
+            .document
+            .as_ref()
+            .unwrap()
+            .contains("\"work_time\": \"50m\""));
+        assert_eq!(store.load(), Ok(data));
+    }
+
+    #[test]
+    fn it_should_migrate_v0() {
+        let store = MemoryStore {
+            document: Some(
+                r#"{
+                    "work_time": "50",
+                    "short_break_time": "2.5",
+                    "total_cycles": "6",
+                    "tasks": ["Task1", "Task2"]
+                }"#
+                .into(),
+            ),
+        };
+
+        let data = store.load().unwrap();
+        assert_eq!(
+            data.tasks,
+            vec![SimpleTask::new("Task1"), SimpleTask::new("Task2")]
+        );
+        assert_eq!(
+            data.settings,
+            Settings {
+                work_time: Duration::from_secs(60 * 50),
+                short_break_time: Duration::from_secs(150),
+                total_cycles: 6,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn it_should_migrate_v1() {
+        let entry = |state: &str, min: u32, task: Option<&str>| {
+            json!({
+                "state": state,
+                "start": format!("2021-12-24T09:{:02}:00Z", min),
+                "end": format!("2021-12-24T09:{:02}:00Z", min + 1),
+                "task": task,
+            })
+        };
+        let mut document = serde_json::to_value(StoreData::default()).unwrap();
+        document["version"] = json!(1);
+        document["history"] = json!([
+            entry("Working", 0, None),
+            entry("Paused", 10, None),
+            entry("Working", 20, None),
+            entry("Break", 30, None),
+            entry("Working", 40, Some("Task1")),
+        ]);
+        let store = MemoryStore {
+            document: Some(document.to_string()),
+        };
+
+        let data = store.load().unwrap();
+        assert_eq!(
+            data.history
+                .iter()
+                .map(|entry| entry.completed)
+                .collect::<Vec<_>>(),
+            vec![false, false, true, true, true]
+        );
+    }
+
+    #[test]
+    fn it_should_reject_newer_versions() {
+        let store = MemoryStore {
+            document: Some(r#"{ "version": 1000 }"#.into()),
+        };
+        assert_eq!(store.load(), Err(StoreError::UnsupportedVersion(1000)));
+    }
+
+    #[test]
+    fn it_should_store_json_files() {
+        let path = std::env::temp_dir()
+            .join(format!("pomododragon-test-{}", std::process::id()))
+            .join("store.json");
+        let mut store = JsonFileStore::new(path.clone());
+        assert_eq!(store.load(), Ok(StoreData::default()));
+
+        let mut data = StoreData::default();
+        data.tasks.push(SimpleTask::new("Task1"));
+        store.save(&data).unwrap();
+        assert_eq!(JsonFileStore::new(path.clone()).load(), Ok(data));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn it_should_restore_saved_sessions() {
+        let mut pomo = SimplePomo::<SimpleTask, InstantTimer>::default();
+        pomo.tasks.push(SimpleTask::new("Task1"));
+        pomo.start();
+        pomo.update();
+
+        let saved_at = Utc::now();
+        let mut store = MemoryStore::default();
+        let data = StoreData {
+            session: Some(SavedSession::new(pomo.snapshot(), saved_at)),
+            ..Default::default()
+        };
+        store.save(&data).unwrap();
+
+        // the tab was closed for the whole work phase
+        let mut restored = SimplePomo::<SimpleTask, InstantTimer>::default();
+        let messages = store
+            .load()
+            .unwrap()
+            .session
+            .unwrap()
+            .restore(&mut restored, saved_at + chrono::Duration::minutes(26));
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(
+            &messages[0],
+            PomoMessage::Transition(Transition {
+                from: PomoState::NotStarted,
+                to: PomoState::Working,
+                ..
+            })
+        ));
+        assert_eq!(restored.state(), PomoState::Break);
+        assert!(restored.tasks().is_empty());
+        assert!(restored.break_timer.elapsed().unwrap() >= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn it_should_keep_completed_tasks() {
+        let mut completed = SimpleTask::new("Task1");
+        completed.complete();
+        let mut data = StoreData {
+            tasks: vec![completed.clone(), SimpleTask::new("Task2")],
+            ..Default::default()
+        };
+        assert_eq!(data.pending_tasks(), vec![SimpleTask::new("Task2")]);
+
+        data.set_pending_tasks(&[SimpleTask::new("Task3")]);
+        assert_eq!(data.tasks, vec![completed, SimpleTask::new("Task3")]);
+    }
+
+    #[test]
+    fn it_should_apply_settings() {
+        let mut pomo = SimplePomo::<SimpleTask, InstantTimer>::default();
+        let settings = Settings {
+            work_time: Duration::from_secs(60 * 50),
+            total_cycles: 2,
+            ..Default::default()
+        };
+        settings.apply(&mut pomo);
+
+        assert_eq!(pomo.work_timer.goal(), Duration::from_secs(60 * 50));
+        assert_eq!(pomo.total_cycles, 2);
+        assert_eq!(pomo.state(), crate::PomoState::NotStarted);
+    }
+}
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 pub trait Task: ToString + Clone {
     type Out;
 
@@ -10,10 +12,10 @@ pub enum TaskKind {
     Simple(SimpleTask),
 }
 
-impl ToString for TaskKind {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for TaskKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            Self::Simple(task) => task.to_string(),
+            Self::Simple(task) => write!(f, "{}", task),
         }
     }
 }
@@ -34,7 +36,7 @@ impl Task for TaskKind {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct SimpleTask {
     completed: bool,
     description: String,
@@ -47,11 +49,15 @@ impl SimpleTask {
             completed: false,
         }
     }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
 }
 
-impl ToString for SimpleTask {
-    fn to_string(&self) -> String {
-        self.description.clone()
+impl std::fmt::Display for SimpleTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.description)
     }
 }
 
@@ -73,7 +79,7 @@ mod tests {
 
     #[test]
     fn it_should_complete() {
-        let mut task = SimpleTask::new("Tets".into());
+        let mut task = SimpleTask::new("Tets");
         assert!(!task.is_completed());
 
         task.complete();
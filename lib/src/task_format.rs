@@ -0,0 +1,285 @@
+use crate::{SimpleTask, Task};
+
+/// Plain text formats task lists can be imported from and exported to
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TaskFormat {
+    /// one task per line, completed tasks start with "x "
+    TodoTxt,
+    /// a checklist of "- [ ]" and "- [x]" items
+    Markdown,
+}
+
+impl TaskFormat {
+    /// guesses the format from a file name
+    /// anything that is not markdown is todo.txt
+    pub fn from_file_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        if name.ends_with(".md") || name.ends_with(".markdown") {
+            Self::Markdown
+        } else {
+            Self::TodoTxt
+        }
+    }
+
+    /// the file name used when exporting
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::TodoTxt => "todo.txt",
+            Self::Markdown => "tasks.md",
+        }
+    }
+
+    /// Parses all tasks in the input
+    /// lines that are not tasks are ignored
+    pub fn parse(&self, input: &str) -> Vec<SimpleTask> {
+        input
+            .lines()
+            .filter_map(|line| match self {
+                Self::TodoTxt => Self::parse_todo_txt(line),
+                Self::Markdown => Self::parse_markdown(line),
+            })
+            .collect()
+    }
+
+    pub fn format(&self, tasks: &[SimpleTask]) -> String {
+        tasks
+            .iter()
+            .map(|task| match (self, task.is_completed()) {
+                (Self::TodoTxt, true) => format!("x {}\n", Self::escape_todo_txt(task, true)),
+                (Self::TodoTxt, false) => format!("{}\n", Self::escape_todo_txt(task, false)),
+                (Self::Markdown, true) => format!("- [x] {}\n", task),
+                (Self::Markdown, false) => format!("- [ ] {}\n", task),
+            })
+            .collect()
+    }
+
+    fn parse_todo_txt(line: &str) -> Option<SimpleTask> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+
+        let (completed, rest) = match line.strip_prefix("x ") {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, line),
+        };
+
+        // the priority of a pending task comes before its creation date
+        let (priority, mut rest) = match Self::split_priority(rest) {
+            Some((priority, rest)) if !completed => (priority, rest),
+            _ => ("", rest),
+        };
+
+        // completion and creation dates are not kept
+        let dates = if completed { 2 } else { 1 };
+        for _ in 0..dates {
+            match Self::strip_date(rest) {
+                Some(date_rest) => rest = date_rest,
+                None => break,
+            }
+        }
+        let rest = rest.strip_prefix('\\').unwrap_or(rest);
+
+        Some(Self::task(&format!("{}{}", priority, rest), completed))
+    }
+
+    /// escapes the start of a description that would be read as a marker or a date
+    fn escape_todo_txt(task: &SimpleTask, completed: bool) -> String {
+        let description = task.to_string();
+        let (priority, rest) = match Self::split_priority(&description) {
+            Some((priority, rest)) if !completed => (priority, rest),
+            _ => ("", description.as_str()),
+        };
+
+        let marker = !completed && priority.is_empty() && rest.starts_with("x ");
+        if marker || rest.starts_with('\\') || Self::strip_date(rest).is_some() {
+            format!("{}\\{}", priority, rest)
+        } else {
+            description
+        }
+    }
+
+    /// splits a leading "(A) " priority from the rest
+    fn split_priority(input: &str) -> Option<(&str, &str)> {
+        let bytes = input.as_bytes();
+        let is_priority = bytes.len() > 4
+            && bytes[0] == b'('
+            && bytes[1].is_ascii_uppercase()
+            && bytes[2] == b')'
+            && bytes[3] == b' ';
+
+        if is_priority {
+            Some(input.split_at(4))
+        } else {
+            None
+        }
+    }
+
+    /// strips a leading yyyy-mm-dd date
+    fn strip_date(input: &str) -> Option<&str> {
+        let (date, rest) = input.split_once(' ')?;
+        let is_date = date.len() == 10
+            && date.char_indices().all(|(i, c)| match i {
+                4 | 7 => c == '-',
+                _ => c.is_ascii_digit(),
+            });
+
+        if is_date {
+            Some(rest.trim_start())
+        } else {
+            None
+        }
+    }
+
+    fn parse_markdown(line: &str) -> Option<SimpleTask> {
+        let line = line.trim_start();
+        let rest = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))
+            .or_else(|| line.strip_prefix("+ "))?;
+
+        let (completed, description) = if let Some(description) = rest.strip_prefix("[ ]") {
+            (false, description)
+        } else if let Some(description) = rest
+            .strip_prefix("[x]")
+            .or_else(|| rest.strip_prefix("[X]"))
+        {
+            (true, description)
+        } else {
+            return None;
+        };
+
+        let description = description.trim();
+        if description.is_empty() {
+            None
+        } else {
+            Some(Self::task(description, completed))
+        }
+    }
+
+    fn task(description: &str, completed: bool) -> SimpleTask {
+        let mut task = SimpleTask::new(description);
+        if completed {
+            task.complete();
+        }
+        task
+    }
+}
+
+impl std::fmt::Display for TaskFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::TodoTxt => "todo.txt",
+                Self::Markdown => "markdown",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for TaskFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "todo.txt" | "todotxt" | "txt" => Ok(Self::TodoTxt),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(format!("Unknown task format '{}'", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completed(description: &str) -> SimpleTask {
+        TaskFormat::task(description, true)
+    }
+
+    #[test]
+    fn it_should_parse_todo_txt() {
+        let tasks = TaskFormat::TodoTxt.parse(
+            "(A) Call mom +family\n\
+            \n\
+            x 2021-12-24 2021-12-20 Buy presents @shop\n\
+            2021-12-20 Write report\n\
+            (B) 2021-12-20 Review report\n",
+        );
+
+        assert_eq!(
+            tasks,
+            vec![
+                SimpleTask::new("(A) Call mom +family"),
+                completed("Buy presents @shop"),
+                SimpleTask::new("Write report"),
+                SimpleTask::new("(B) Review report"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_parse_markdown() {
+        let tasks = TaskFormat::Markdown.parse(
+            "# Today\n\
+            - [ ] Write report\n\
+            \x20 * [x] Buy presents\n\
+            - [X] Call mom\n\
+            - not a task\n\
+            - [ ]\n",
+        );
+
+        assert_eq!(
+            tasks,
+            vec![
+                SimpleTask::new("Write report"),
+                completed("Buy presents"),
+                completed("Call mom"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_round_trip() {
+        let tasks = vec![SimpleTask::new("Task1"), completed("Task2")];
+
+        for format in [TaskFormat::TodoTxt, TaskFormat::Markdown] {
+            assert_eq!(format.parse(&format.format(&tasks)), tasks);
+        }
+        assert_eq!(
+            TaskFormat::Markdown.format(&tasks),
+            "- [ ] Task1\n- [x] Task2\n"
+        );
+    }
+
+    #[test]
+    fn it_should_round_trip_todo_txt_markers() {
+        let tasks = vec![
+            SimpleTask::new("x marks the spot"),
+            SimpleTask::new("2021-12-24 Buy presents"),
+            SimpleTask::new("(A) 2021-12-24 Buy presents"),
+            SimpleTask::new("\\server\\share"),
+            completed("2021-12-24 Buy presents"),
+            completed("x marks the spot"),
+        ];
+
+        assert_eq!(
+            TaskFormat::TodoTxt.parse(&TaskFormat::TodoTxt.format(&tasks)),
+            tasks
+        );
+        assert_eq!(
+            TaskFormat::TodoTxt.format(&tasks[..3]),
+            "\\x marks the spot\n\
+            \\2021-12-24 Buy presents\n\
+            (A) \\2021-12-24 Buy presents\n"
+        );
+    }
+
+    #[test]
+    fn it_should_guess_format() {
+        assert_eq!(TaskFormat::from_file_name("Tasks.MD"), TaskFormat::Markdown);
+        assert_eq!(TaskFormat::from_file_name("todo.txt"), TaskFormat::TodoTxt);
+    }
+}
@@ -1,6 +1,7 @@
 use instant::Instant; // portable instant for native and wasm
-use std::collections::HashMap;
 use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::SystemTime;
 
 pub trait Timer: Clone {
     type Out;
@@ -31,6 +32,13 @@ pub trait Timer: Clone {
     /// start the timer
     fn start(&mut self) -> Self::Out;
 
+    /// start the timer with a different goal
+    /// the goal only applies until the timer is started again,
+    /// timers that can not change their goal keep their own
+    fn start_with_goal(&mut self, _goal: Duration) -> Self::Out {
+        self.start()
+    }
+
     /// reset is usually the same as start
     fn reset(&mut self) -> Self::Out {
         self.start()
@@ -53,7 +61,18 @@ pub trait Timer: Clone {
     }
 
     fn has_started(&self) -> bool {
-        self.elapsed() != None
+        self.elapsed().is_some()
+    }
+
+    /// time left until the goal is reached
+    /// None if the timer is not running
+    fn remaining(&self) -> Option<Duration> {
+        if self.is_paused() {
+            None
+        } else {
+            self.elapsed()
+                .map(|elapsed| self.goal().saturating_sub(elapsed))
+        }
     }
 
     fn percentage(&self) -> f64 {
@@ -66,6 +85,24 @@ pub trait Timer: Clone {
     fn is_paused(&self) -> bool;
     fn pause(&mut self);
     fn resume(&mut self);
+
+    /// moves the goal back, e.g. to not count time
+    /// the system spent suspended
+    fn extend(&mut self, by: Duration);
+
+    /// returns how far the wall-clock jumped ahead
+    /// since the last call, e.g. because the system was suspended.
+    /// Timers that can not detect jumps always return None
+    fn take_clock_jump(&mut self) -> Option<Duration> {
+        None
+    }
+
+    /// how long the timer has been paused
+    fn paused_for(&self) -> Option<Duration>;
+
+    /// continues a timer that already ran for elapsed
+    /// a paused timer is paused from now on
+    fn restore(&mut self, elapsed: Duration, goal: Duration, paused: bool);
 }
 
 /// Timer based on simple instant and duration
@@ -75,17 +112,20 @@ pub struct InstantTimer {
     paused: bool,
     paused_instant: Option<Instant>,
     current_goal: Duration,
-    goal: Duration,
+    base_goal: Duration,
+    // time that passed before start, used by restore
+    offset: Duration,
 }
 
 impl InstantTimer {
     pub fn new(goal: Duration) -> Self {
         Self {
             start: None,
-            goal,
+            base_goal: goal,
             paused: false,
             paused_instant: None,
             current_goal: goal,
+            offset: Duration::from_secs(0),
         }
     }
 }
@@ -98,12 +138,19 @@ impl Timer for InstantTimer {
     }
 
     fn start(&mut self) {
-        self.current_goal = self.goal;
+        self.start_with_goal(self.base_goal);
+    }
+
+    fn start_with_goal(&mut self, goal: Duration) {
+        self.current_goal = goal;
+        self.offset = Duration::from_secs(0);
+        self.paused = false;
+        self.paused_instant = None;
         self.start = Some(Instant::now());
     }
 
     fn elapsed(&self) -> Option<Duration> {
-        self.start.map(|start| start.elapsed())
+        self.start.map(|start| start.elapsed() + self.offset)
     }
 
     fn goal(&self) -> Duration {
@@ -129,75 +176,538 @@ impl Timer for InstantTimer {
             self.paused_instant = None;
         }
     }
+
+    fn extend(&mut self, by: Duration) {
+        self.current_goal += by;
+    }
+
+    fn paused_for(&self) -> Option<Duration> {
+        self.paused_instant.map(|paused| paused.elapsed())
+    }
+
+    fn restore(&mut self, elapsed: Duration, goal: Duration, paused: bool) {
+        // the instant may not be able to go back far enough
+        // on wasm, so the elapsed time is kept as offset
+        let now = Instant::now();
+        self.start = Some(now);
+        self.offset = elapsed;
+        self.current_goal = goal;
+        self.paused = paused;
+        self.paused_instant = if paused { Some(now) } else { None };
+    }
+}
+
+/// Timer based on wall-clock deadlines.
+/// Time the system spends suspended counts towards the goal.
+/// Suspends and clock changes are detected by comparing the
+/// wall-clock to the monotonic clock, jumps ahead are reported
+/// by take_clock_jump, jumps back are ignored.
+/// The wall-clock is not available on wasm.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone)]
+pub struct SystemTimer {
+    start: Option<SystemTime>,
+    paused_at: Option<SystemTime>,
+    current_goal: Duration,
+    base_goal: Duration,
+    last_check: Option<(SystemTime, Instant)>,
+    /// smaller differences between the clocks are not reported
+    pub jump_threshold: Duration,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SystemTimer {
+    pub fn new(goal: Duration) -> Self {
+        Self {
+            start: None,
+            paused_at: None,
+            current_goal: goal,
+            base_goal: goal,
+            last_check: None,
+            jump_threshold: Duration::from_secs(5),
+        }
+    }
+
+    fn checkpoint(&mut self) {
+        self.last_check = Some((SystemTime::now(), Instant::now()));
+    }
+
+    /// pretends the wall-clock jumped ahead while
+    /// the monotonic clock did not
+    #[cfg(test)]
+    pub(crate) fn simulate_suspend(&mut self, gap: Duration) {
+        self.start = self.start.map(|start| start - gap);
+        self.last_check = self.last_check.map(|(wall, mono)| (wall - gap, mono));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Timer for SystemTimer {
+    type Out = ();
+
+    fn from_goal(goal: Duration) -> Self {
+        Self::new(goal)
+    }
+
+    fn start(&mut self) {
+        self.start_with_goal(self.base_goal);
+    }
+
+    fn start_with_goal(&mut self, goal: Duration) {
+        self.current_goal = goal;
+        self.paused_at = None;
+        self.start = Some(SystemTime::now());
+        self.checkpoint();
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        // a clock that went back before the start counts as no time
+        self.start
+            .map(|start| start.elapsed().unwrap_or_else(|_| Duration::from_secs(0)))
+    }
+
+    fn goal(&self) -> Duration {
+        self.current_goal
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    fn pause(&mut self) {
+        if !self.is_paused() {
+            self.paused_at = Some(SystemTime::now());
+        }
+    }
+
+    fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.current_goal += paused_at
+                .elapsed()
+                .unwrap_or_else(|_| Duration::from_secs(0));
+            self.checkpoint();
+        }
+    }
+
+    fn extend(&mut self, by: Duration) {
+        self.current_goal += by;
+    }
+
+    fn paused_for(&self) -> Option<Duration> {
+        self.paused_at.map(|paused_at| {
+            paused_at
+                .elapsed()
+                .unwrap_or_else(|_| Duration::from_secs(0))
+        })
+    }
+
+    fn restore(&mut self, elapsed: Duration, goal: Duration, paused: bool) {
+        let now = SystemTime::now();
+        self.start = Some(now - elapsed);
+        self.current_goal = goal;
+        self.paused_at = if paused { Some(now) } else { None };
+        self.checkpoint();
+    }
+
+    fn take_clock_jump(&mut self) -> Option<Duration> {
+        let (wall, mono) = self.last_check?;
+        if self.is_paused() {
+            return None;
+        }
+        self.checkpoint();
+
+        let wall_delta = wall.elapsed().unwrap_or_else(|_| Duration::from_secs(0));
+        let mono_delta = mono.elapsed();
+
+        if wall_delta >= mono_delta {
+            let gap = wall_delta - mono_delta;
+            Some(gap).filter(|gap| *gap > self.jump_threshold)
+        } else {
+            // the clock went back, keep the elapsed time intact
+            let gap = mono_delta - wall_delta;
+            if gap > self.jump_threshold {
+                self.start = self.start.map(|start| start - gap);
+            }
+            None
+        }
+    }
 }
 
+const NANOS_PER_MICRO: u128 = 1_000;
+const NANOS_PER_MILLI: u128 = 1_000_000;
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+const NANOS_PER_MIN: u128 = 60 * NANOS_PER_SEC;
+const NANOS_PER_HOUR: u128 = 60 * NANOS_PER_MIN;
+const NANOS_PER_DAY: u128 = 24 * NANOS_PER_HOUR;
+
+/// All units from largest to smallest
+/// The first name of each unit is used for formatting
+const UNITS: &[(u128, &[&str])] = &[
+    (NANOS_PER_DAY, &["d", "day", "days"]),
+    (NANOS_PER_HOUR, &["h", "hr", "hrs", "hour", "hours"]),
+    (NANOS_PER_MIN, &["m", "min", "mins", "minute", "minutes"]),
+    (NANOS_PER_SEC, &["s", "sec", "secs", "second", "seconds"]),
+    (
+        NANOS_PER_MILLI,
+        &[
+            "ms",
+            "msec",
+            "msecs",
+            "millis",
+            "millisecond",
+            "milliseconds",
+        ],
+    ),
+    (
+        NANOS_PER_MICRO,
+        &[
+            "us",
+            "µs",
+            "usec",
+            "usecs",
+            "micros",
+            "microsecond",
+            "microseconds",
+        ],
+    ),
+    (
+        1,
+        &["ns", "nsec", "nsecs", "nanos", "nanosecond", "nanoseconds"],
+    ),
+];
+
+/// Reasons a time string can not be parsed
+/// Positions are byte offsets into the input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimeParseError {
+    Empty,
+    ExpectedNumber {
+        position: usize,
+    },
+    UnknownUnit {
+        unit: String,
+        position: usize,
+    },
+    UnexpectedChar {
+        c: char,
+        position: usize,
+    },
+    /// a number without unit that is not the last value
+    /// or follows the smallest unit
+    MissingUnit {
+        position: usize,
+    },
+    /// minutes or seconds of a clock time are 60 or more
+    ClockOutOfRange {
+        position: usize,
+    },
+    Overflow,
+}
+
+impl std::fmt::Display for TimeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "No time given"),
+            Self::ExpectedNumber { position } => {
+                write!(f, "Expected a number at position {}", position)
+            }
+            Self::UnknownUnit { unit, position } => {
+                write!(f, "Unknown unit '{}' at position {}", unit, position)
+            }
+            Self::UnexpectedChar { c, position } => {
+                write!(f, "Unexpected '{}' at position {}", c, position)
+            }
+            Self::MissingUnit { position } => {
+                write!(f, "Missing unit for number at position {}", position)
+            }
+            Self::ClockOutOfRange { position } => {
+                write!(f, "Clock value at position {} must be below 60", position)
+            }
+            Self::Overflow => write!(f, "Time is too large"),
+        }
+    }
+}
+
+impl std::error::Error for TimeParseError {}
+
 /// A time string parser intended to be used for simple time input
 pub struct TimeParser;
 impl TimeParser {
     /// Parses a time string of form 1h5m40s100ms200us
-    /// Spaces and tabs are ignored
-    /// Returns as duration, or None on invalid input
-    pub fn parse(time_str: &str) -> Option<Duration> {
-        let mut start;
-        let mut current = 0;
-        let mut result = 0;
-
-        let mut operators = HashMap::new();
-        operators.insert("\0", 1000);
-        operators.insert("ms", 1);
-        operators.insert("s", 1000);
-        operators.insert("m", 60000);
-        operators.insert("h", 3600000);
-
-        // always scan number+operator
-        while current < time_str.len() {
-            start = current;
-            // TODO ignore white-spaces between numbers/operators
-            let num = Self::scan_num(time_str, start, &mut current)?;
-
-            start = current;
-            let op = Self::scan_operator(time_str, start, &mut current, &operators)?;
-            result += num * op;
+    /// or a clock time of form mm:ss or hh:mm:ss
+    ///
+    /// Supported units are d, h, m, s, ms, us and ns as well as
+    /// long names like min, hours or seconds.
+    /// Values may be decimals (1.5h) and whitespace is ignored.
+    /// A trailing number without unit uses the unit after the
+    /// previous one (1m30 is 1m30s), a lone number is in seconds.
+    pub fn parse(time_str: &str) -> Result<Duration, TimeParseError> {
+        let mut scanner = Scanner::new(time_str);
+        scanner.skip_whitespace();
+        if scanner.peek().is_none() {
+            return Err(TimeParseError::Empty);
+        }
+
+        if time_str.contains(':') {
+            Self::parse_clock(&mut scanner)
+        } else {
+            Self::parse_units(&mut scanner)
         }
-        Some(Duration::from_millis(result))
     }
 
-    fn scan_operator(
-        time_str: &str,
-        start: usize,
-        current: &mut usize,
-        operators: &HashMap<&str, u64>,
-    ) -> Option<u64> {
-        while Self::is_alpha(time_str.chars().nth(*current).unwrap_or('\0')) {
-            *current += 1;
+    /// Like parse, but a lone number is in minutes
+    /// as timer settings have always been given in minutes
+    pub fn parse_minutes(time_str: &str) -> Result<Duration, TimeParseError> {
+        if time_str.trim().parse::<f64>().is_ok() {
+            Self::parse(&format!("{}m", time_str.trim()))
+        } else {
+            Self::parse(time_str)
         }
-        // no operator? return 1, but only if
-        // current is end of string too
-        if start == *current && *current >= time_str.len() {
-            Some(1)
-        } else if start == *current {
-            None
+    }
+
+    /// Formats a duration so that parse returns the same duration
+    /// e.g. 1h30m or 1m30s500ms
+    pub fn format(duration: Duration) -> String {
+        let mut nanos = duration.as_nanos();
+        if nanos == 0 {
+            return "0s".into();
+        }
+
+        let mut result = String::new();
+        for (unit, names) in UNITS {
+            let value = nanos / unit;
+            if value > 0 {
+                result.push_str(&format!("{}{}", value, names[0]));
+                nanos -= value * unit;
+            }
+        }
+        result
+    }
+
+    /// Formats a duration as mm:ss or h:mm:ss
+    /// fractions of a second are dropped
+    pub fn format_clock(duration: Duration) -> String {
+        let secs = duration.as_secs();
+        let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+        if hours > 0 {
+            format!("{}:{:02}:{:02}", hours, mins, secs)
         } else {
-            let operator = &time_str[start..*current];
-            operators.get(operator).copied()
+            format!("{:02}:{:02}", mins, secs)
+        }
+    }
+
+    fn parse_units(scanner: &mut Scanner) -> Result<Duration, TimeParseError> {
+        let mut total: u128 = 0;
+        // index into UNITS of the previous unit
+        let mut prev_unit: Option<usize> = None;
+
+        while scanner.peek().is_some() {
+            let position = scanner.position();
+            let (num, denom) = scanner.number()?;
+            scanner.skip_whitespace();
+
+            let unit_position = scanner.position();
+            let name = scanner.word();
+            let unit = if name.is_empty() {
+                // a number without unit is only allowed at the end
+                match scanner.peek() {
+                    Some(c) if c.is_ascii_digit() => {
+                        return Err(TimeParseError::MissingUnit { position })
+                    }
+                    Some(c) => {
+                        return Err(TimeParseError::UnexpectedChar {
+                            c,
+                            position: unit_position,
+                        })
+                    }
+                    None => (),
+                }
+                match prev_unit {
+                    Some(prev) if prev + 1 < UNITS.len() => prev + 1,
+                    Some(_) => return Err(TimeParseError::MissingUnit { position }),
+                    None => 3, // seconds
+                }
+            } else {
+                let lower = name.to_lowercase();
+                UNITS
+                    .iter()
+                    .position(|(_, names)| names.contains(&lower.as_str()))
+                    .ok_or(TimeParseError::UnknownUnit {
+                        unit: name,
+                        position: unit_position,
+                    })?
+            };
+            scanner.skip_whitespace();
+
+            let value = num
+                .checked_mul(UNITS[unit].0)
+                .ok_or(TimeParseError::Overflow)?
+                / denom;
+            total = total.checked_add(value).ok_or(TimeParseError::Overflow)?;
+            prev_unit = Some(unit);
+        }
+
+        Self::to_duration(total)
+    }
+
+    fn parse_clock(scanner: &mut Scanner) -> Result<Duration, TimeParseError> {
+        let mut parts = vec![];
+
+        loop {
+            scanner.skip_whitespace();
+            let position = scanner.position();
+            parts.push((position, scanner.number()?));
+            scanner.skip_whitespace();
+
+            match scanner.next() {
+                Some((_, ':')) => (),
+                Some((position, c)) => return Err(TimeParseError::UnexpectedChar { c, position }),
+                None => break,
+            }
+        }
+
+        if parts.len() > 3 {
+            return Err(TimeParseError::UnexpectedChar {
+                c: ':',
+                position: parts[3].0 - 1,
+            });
         }
+
+        let units = &UNITS[4 - parts.len()..4];
+        let mut total: u128 = 0;
+        for (i, ((position, (num, denom)), (unit, _))) in parts.iter().zip(units).enumerate() {
+            // only the seconds may have a fraction
+            if *denom != 1 && i != parts.len() - 1 {
+                return Err(TimeParseError::UnexpectedChar {
+                    c: '.',
+                    position: *position,
+                });
+            }
+            // everything but the leading value must be below 60
+            if i > 0 && *num >= 60 * denom {
+                return Err(TimeParseError::ClockOutOfRange {
+                    position: *position,
+                });
+            }
+
+            let value = num.checked_mul(*unit).ok_or(TimeParseError::Overflow)? / denom;
+            total = total.checked_add(value).ok_or(TimeParseError::Overflow)?;
+        }
+
+        Self::to_duration(total)
+    }
+
+    fn to_duration(nanos: u128) -> Result<Duration, TimeParseError> {
+        let secs = u64::try_from(nanos / NANOS_PER_SEC).map_err(|_| TimeParseError::Overflow)?;
+        Ok(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+    }
+}
+
+/// Serializes durations as time strings, e.g. 25m or 1h30m
+/// use with #[serde(with = "serde_duration")]
+pub mod serde_duration {
+    use super::TimeParser;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&TimeParser::format(*duration))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let time_str = String::deserialize(deserializer)?;
+        TimeParser::parse(&time_str).map_err(de::Error::custom)
     }
+}
 
-    fn scan_num(time_str: &str, start: usize, current: &mut usize) -> Option<u64> {
-        while Self::is_numeric(time_str.chars().nth(*current).unwrap_or('\0')) {
-            *current += 1;
+/// Single pass scanner over a time string
+struct Scanner<'a> {
+    input: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            chars: input.char_indices().peekable(),
         }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        self.chars.next()
+    }
 
-        let number = &time_str[start..*current];
-        number.parse::<u64>().ok()
+    fn position(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map(|(i, _)| *i)
+            .unwrap_or(self.input.len())
     }
 
-    fn is_numeric(c: char) -> bool {
-        ('0'..='9').contains(&c)
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
     }
 
-    fn is_alpha(c: char) -> bool {
-        ('a'..='z').contains(&c) || ('A'..='Z').contains(&c)
+    /// scans a decimal number and returns it as a fraction
+    fn number(&mut self) -> Result<(u128, u128), TimeParseError> {
+        let position = self.position();
+        let mut num: u128 = 0;
+        let mut denom: u128 = 1;
+        let mut digits = 0;
+        let mut fraction = false;
+
+        while let Some(c) = self.peek() {
+            if let Some(digit) = c.to_digit(10) {
+                // digits beyond the precision of a duration are dropped
+                if !fraction || denom < NANOS_PER_SEC * NANOS_PER_SEC {
+                    num = num
+                        .checked_mul(10)
+                        .and_then(|num| num.checked_add(digit as u128))
+                        .ok_or(TimeParseError::Overflow)?;
+                    if fraction {
+                        denom *= 10;
+                    }
+                }
+                digits += 1;
+            } else if c == '.' && !fraction {
+                fraction = true;
+            } else {
+                break;
+            }
+            self.next();
+        }
+
+        if digits == 0 {
+            match self.peek() {
+                Some(c) if c != '.' => Err(TimeParseError::UnexpectedChar { c, position }),
+                _ => Err(TimeParseError::ExpectedNumber { position }),
+            }
+        } else {
+            Ok((num, denom))
+        }
+    }
+
+    /// scans a unit name
+    fn word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.peek().filter(|c| c.is_alphabetic()) {
+            word.push(c);
+            self.next();
+        }
+        word
     }
 }
 
@@ -260,17 +770,226 @@ mod tests {
         assert!(!timer.is_paused());
     }
 
+    #[test]
+    fn it_should_complete_system_timer() {
+        let mut timer = SystemTimer::new(Duration::from_millis(100));
+        assert!(!timer.has_started());
+        timer.start();
+        assert!(!timer.is_completed());
+
+        std::thread::sleep(Duration::from_millis(101));
+        assert!(timer.is_completed());
+    }
+
+    #[test]
+    fn it_should_pause_system_timer() {
+        let mut timer = SystemTimer::new(Duration::from_millis(100));
+        timer.start();
+        timer.pause();
+        std::thread::sleep(Duration::from_millis(150));
+        assert!(!timer.is_completed());
+        timer.resume();
+
+        assert!(timer.goal().as_millis() >= 250);
+        assert!(!timer.is_completed());
+    }
+
+    #[test]
+    fn it_should_detect_clock_jumps() {
+        let mut timer = SystemTimer::new(Duration::from_secs(60));
+        assert_eq!(timer.take_clock_jump(), None);
+        timer.start();
+        assert_eq!(timer.take_clock_jump(), None);
+
+        timer.simulate_suspend(Duration::from_secs(120));
+        let gap = timer.take_clock_jump().unwrap();
+        assert!(gap >= Duration::from_secs(119) && gap <= Duration::from_secs(121));
+        assert!(timer.is_completed());
+
+        // the jump is only reported once
+        assert_eq!(timer.take_clock_jump(), None);
+    }
+
+    #[test]
+    fn it_should_restore_timers() {
+        let mut instant = InstantTimer::new(Duration::from_secs(60));
+        let mut system = SystemTimer::new(Duration::from_secs(60));
+        instant.restore(Duration::from_secs(30), Duration::from_secs(90), false);
+        system.restore(Duration::from_secs(30), Duration::from_secs(90), true);
+
+        let elapsed = instant.elapsed().unwrap();
+        assert!(elapsed >= Duration::from_secs(30) && elapsed < Duration::from_secs(31));
+        assert_eq!(instant.goal(), Duration::from_secs(90));
+        assert!(!instant.is_paused());
+
+        let elapsed = system.elapsed().unwrap();
+        assert!(elapsed >= Duration::from_secs(30) && elapsed < Duration::from_secs(31));
+        assert!(system.is_paused());
+        assert!(system.paused_for().unwrap() < Duration::from_secs(1));
+    }
+
     #[test]
     fn it_should_parse_time_str() {
         let ms = TimeParser::parse("1h20m10s5").unwrap();
         assert_eq!(
             ms,
-            Duration::from_millis((1 * 3600000) + (20 * 60000) + (10 * 1000) + 5)
+            Duration::from_millis(3600000 + (20 * 60000) + (10 * 1000) + 5)
         );
     }
 
     #[test]
     fn it_should_not_parse_time_str_bad_operator() {
-        assert_eq!(TimeParser::parse("1h20m10@5"), None);
+        assert_eq!(
+            TimeParser::parse("1h20m10@5"),
+            Err(TimeParseError::UnexpectedChar {
+                c: '@',
+                position: 7
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_parse_time_str_with_whitespace_and_long_units() {
+        assert_eq!(
+            TimeParser::parse(" 1 hour 30 min\t"),
+            Ok(Duration::from_secs(90 * 60))
+        );
+        assert_eq!(
+            TimeParser::parse("2 Days 1sec"),
+            Ok(Duration::from_secs(2 * 86400 + 1))
+        );
+        assert_eq!(
+            TimeParser::parse("5ms 20us 7ns"),
+            Ok(Duration::from_nanos(5_020_007))
+        );
+    }
+
+    #[test]
+    fn it_should_parse_decimals() {
+        assert_eq!(TimeParser::parse("1.5h"), Ok(Duration::from_secs(90 * 60)));
+        assert_eq!(TimeParser::parse("0.25s"), Ok(Duration::from_millis(250)));
+        assert_eq!(TimeParser::parse("1.h"), Ok(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn it_should_parse_bare_numbers() {
+        assert_eq!(TimeParser::parse("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(TimeParser::parse("1m30"), Ok(Duration::from_secs(90)));
+        assert_eq!(TimeParser::parse("1h30"), Ok(Duration::from_secs(90 * 60)));
+        assert_eq!(
+            TimeParser::parse("30 1m"),
+            Err(TimeParseError::MissingUnit { position: 0 })
+        );
+        assert_eq!(
+            TimeParser::parse("1ns5"),
+            Err(TimeParseError::MissingUnit { position: 3 })
+        );
+    }
+
+    #[test]
+    fn it_should_parse_minutes() {
+        assert_eq!(
+            TimeParser::parse_minutes(" 25 "),
+            Ok(Duration::from_secs(25 * 60))
+        );
+        assert_eq!(
+            TimeParser::parse_minutes("1.5"),
+            Ok(Duration::from_secs(90))
+        );
+        assert_eq!(
+            TimeParser::parse_minutes("90s"),
+            Ok(Duration::from_secs(90))
+        );
+        assert_eq!(
+            TimeParser::parse_minutes("1h30m"),
+            Ok(Duration::from_secs(90 * 60))
+        );
+        assert_eq!(TimeParser::parse_minutes(""), Err(TimeParseError::Empty));
+    }
+
+    #[test]
+    fn it_should_parse_clock_times() {
+        assert_eq!(TimeParser::parse("25:00"), Ok(Duration::from_secs(25 * 60)));
+        assert_eq!(TimeParser::parse("1:02:03"), Ok(Duration::from_secs(3723)));
+        assert_eq!(
+            TimeParser::parse("00:01.5"),
+            Ok(Duration::from_millis(1500))
+        );
+        assert_eq!(TimeParser::parse("90:00"), Ok(Duration::from_secs(90 * 60)));
+        assert_eq!(
+            TimeParser::parse("1:60:00"),
+            Err(TimeParseError::ClockOutOfRange { position: 2 })
+        );
+        assert_eq!(
+            TimeParser::parse("1:2:3:4"),
+            Err(TimeParseError::UnexpectedChar {
+                c: ':',
+                position: 5
+            })
+        );
+        assert_eq!(
+            TimeParser::parse("1:"),
+            Err(TimeParseError::ExpectedNumber { position: 2 })
+        );
+    }
+
+    #[test]
+    fn it_should_report_errors() {
+        assert_eq!(TimeParser::parse(""), Err(TimeParseError::Empty));
+        assert_eq!(TimeParser::parse("  "), Err(TimeParseError::Empty));
+        assert_eq!(
+            TimeParser::parse("5 parsecs"),
+            Err(TimeParseError::UnknownUnit {
+                unit: "parsecs".into(),
+                position: 2
+            })
+        );
+        assert_eq!(
+            TimeParser::parse("m"),
+            Err(TimeParseError::UnexpectedChar {
+                c: 'm',
+                position: 0
+            })
+        );
+        assert_eq!(
+            TimeParser::parse("99999999999999999999999d"),
+            Err(TimeParseError::Overflow)
+        );
+    }
+
+    #[test]
+    fn it_should_format_durations() {
+        assert_eq!(TimeParser::format(Duration::from_secs(0)), "0s");
+        assert_eq!(TimeParser::format(Duration::from_secs(25 * 60)), "25m");
+        assert_eq!(
+            TimeParser::format(Duration::from_millis(90_500)),
+            "1m30s500ms"
+        );
+        assert_eq!(
+            TimeParser::format(Duration::from_secs(86400 + 3600)),
+            "1d1h"
+        );
+
+        assert_eq!(TimeParser::format_clock(Duration::from_secs(90)), "01:30");
+        assert_eq!(
+            TimeParser::format_clock(Duration::from_secs(3723)),
+            "1:02:03"
+        );
+    }
+
+    #[test]
+    fn it_should_round_trip() {
+        for duration in [
+            Duration::from_secs(0),
+            Duration::from_nanos(1),
+            Duration::from_millis(1500),
+            Duration::new(123_456, 789_012_345),
+            Duration::from_secs(u64::MAX),
+        ] {
+            assert_eq!(
+                TimeParser::parse(&TimeParser::format(duration)),
+                Ok(duration)
+            );
+        }
     }
 }
@@ -0,0 +1,341 @@
+use crate::{TimeParseError, TimeParser};
+use chrono::{Local, NaiveTime, Timelike};
+use std::time::Duration;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Reasons an absolute time can not be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UntilParseError {
+    Empty,
+    UnknownTime { time: String },
+    Duration(TimeParseError),
+}
+
+impl std::fmt::Display for UntilParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "No time given"),
+            Self::UnknownTime { time } => write!(f, "Unknown time '{}'", time),
+            Self::Duration(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for UntilParseError {}
+
+impl From<TimeParseError> for UntilParseError {
+    fn from(err: TimeParseError) -> Self {
+        Self::Duration(err)
+    }
+}
+
+/// A goal given either as a length or as an absolute time
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Goal {
+    /// e.g. 25m, the length of every phase
+    Duration(Duration),
+    /// e.g. until 14:30, the time of day a phase ends at
+    Until(NaiveTime),
+}
+
+impl Goal {
+    /// the length of a phase that starts now
+    pub fn duration(&self) -> Duration {
+        self.duration_at(Local::now().time())
+    }
+
+    /// the length of a phase that starts at now,
+    /// a time that already passed today refers to tomorrow
+    pub fn duration_at(&self, now: NaiveTime) -> Duration {
+        match self {
+            Self::Duration(duration) => *duration,
+            Self::Until(time) => {
+                let until = *time - now;
+                let until = if until > chrono::Duration::zero() {
+                    until
+                } else {
+                    until + chrono::Duration::days(1)
+                };
+                until.to_std().unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Parses absolute times into the duration from now until then
+///
+/// Accepts times of day like 14:30, 2:30pm or named times like noon,
+/// optionally prefixed with until and shifted by a duration,
+/// e.g. "until 14:30", "until noon" or "+45m after lunch".
+/// A time that already passed today refers to tomorrow.
+#[derive(Clone, Debug)]
+pub struct UntilParser {
+    anchors: Vec<(String, NaiveTime)>,
+}
+
+impl Default for UntilParser {
+    fn default() -> Self {
+        let at = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap_or_default();
+        Self {
+            anchors: vec![
+                ("midnight".into(), at(0)),
+                ("noon".into(), at(12)),
+                ("lunch".into(), at(12)),
+                ("eod".into(), at(17)),
+            ],
+        }
+    }
+}
+
+impl UntilParser {
+    /// adds or replaces a named time
+    pub fn anchor(mut self, name: &str, time: NaiveTime) -> Self {
+        let name = name.to_lowercase();
+        self.anchors.retain(|(x, _)| *x != name);
+        self.anchors.push((name, time));
+        self
+    }
+
+    /// Parses a goal that is either a duration (see TimeParser)
+    /// or an absolute time.
+    /// Durations come first, so a bare 14:30 is 14m30s
+    /// and times of day need a prefix like "until 14:30"
+    pub fn parse_goal(&self, input: &str) -> Result<Goal, UntilParseError> {
+        let duration_err = match TimeParser::parse(input) {
+            Ok(duration) => return Ok(Goal::Duration(duration)),
+            Err(err) => err,
+        };
+
+        self.parse_time(input)
+            .map(Goal::Until)
+            .map_err(|err| match err {
+                // without a prefix the input was most likely meant as a duration
+                UntilParseError::UnknownTime { .. }
+                    if Self::strip_until(&input.to_lowercase()).is_none() =>
+                {
+                    UntilParseError::Duration(duration_err)
+                }
+                err => err,
+            })
+    }
+
+    /// Returns the duration from now until the given time
+    pub fn parse(&self, input: &str) -> Result<Duration, UntilParseError> {
+        self.parse_at(input, Local::now().time())
+    }
+
+    /// Returns the duration from now until the given time
+    pub fn parse_at(&self, input: &str, now: NaiveTime) -> Result<Duration, UntilParseError> {
+        self.parse_time_at(input, now)
+            .map(|time| Goal::Until(time).duration_at(now))
+    }
+
+    /// Returns the time of day the input refers to,
+    /// relative times like +45m start now
+    pub fn parse_time(&self, input: &str) -> Result<NaiveTime, UntilParseError> {
+        self.parse_time_at(input, Local::now().time())
+    }
+
+    /// Returns the time of day the input refers to,
+    /// relative times like +45m start at now
+    pub fn parse_time_at(&self, input: &str, now: NaiveTime) -> Result<NaiveTime, UntilParseError> {
+        let input = input.trim().to_lowercase();
+        let input = Self::strip_until(&input).unwrap_or(&input).trim();
+
+        if input.is_empty() {
+            return Err(UntilParseError::Empty);
+        }
+
+        // shifted time: +45m after lunch
+        for (word, after) in [(" after ", true), (" before ", false)] {
+            if let Some((offset, time)) = input.split_once(word) {
+                let offset = TimeParser::parse(offset.trim_start_matches('+'))?;
+                let offset = offset.as_secs() % SECS_PER_DAY;
+                let target = Self::secs_of_day(self.time_of_day(time.trim())?);
+                let target = if after {
+                    target + offset
+                } else {
+                    target + SECS_PER_DAY - offset
+                };
+                return Ok(Self::from_secs_of_day(target % SECS_PER_DAY));
+            }
+        }
+
+        // relative time: +45m, counted from the exact current time
+        if let Some(offset) = input.strip_prefix('+') {
+            let offset = TimeParser::parse(offset)?;
+            return Ok(now
+                + chrono::Duration::seconds((offset.as_secs() % SECS_PER_DAY) as i64)
+                + chrono::Duration::nanoseconds(offset.subsec_nanos() as i64));
+        }
+
+        self.time_of_day(input)
+    }
+
+    /// strips a leading until or till
+    fn strip_until(input: &str) -> Option<&str> {
+        ["until", "till"]
+            .iter()
+            .filter_map(|prefix| input.trim_start().strip_prefix(prefix))
+            .find(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+    }
+
+    fn from_secs_of_day(secs: u64) -> NaiveTime {
+        NaiveTime::from_num_seconds_from_midnight_opt(secs as u32, 0).unwrap_or_default()
+    }
+
+    fn secs_of_day(time: NaiveTime) -> u64 {
+        time.num_seconds_from_midnight() as u64
+    }
+
+    /// parses 14:30, 14:30:15, 2pm, 2:30 pm or a named time
+    fn time_of_day(&self, input: &str) -> Result<NaiveTime, UntilParseError> {
+        if let Some((_, time)) = self.anchors.iter().find(|(name, _)| name == input) {
+            return Ok(*time);
+        }
+
+        let unknown = || UntilParseError::UnknownTime { time: input.into() };
+
+        let (clock, meridiem) = if let Some(clock) = input.strip_suffix("am") {
+            (clock.trim(), Some(0))
+        } else if let Some(clock) = input.strip_suffix("pm") {
+            (clock.trim(), Some(12))
+        } else {
+            (input, None)
+        };
+
+        let mut parts = clock.split(':').map(|part| part.parse::<u32>().ok());
+        let hour = parts.next().flatten().ok_or_else(unknown)?;
+        let minute = parts.next().map_or(Some(0), |x| x).ok_or_else(unknown)?;
+        let second = parts.next().map_or(Some(0), |x| x).ok_or_else(unknown)?;
+        if parts.next().is_some() || (meridiem.is_none() && !clock.contains(':')) {
+            return Err(unknown());
+        }
+
+        let hour = match meridiem {
+            Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+            Some(_) => return Err(unknown()),
+            None => hour,
+        };
+
+        NaiveTime::from_hms_opt(hour, minute, second).ok_or_else(unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn mins(mins: u64) -> Duration {
+        Duration::from_secs(mins * 60)
+    }
+
+    #[test]
+    fn it_should_parse_clock_times() {
+        let parser = UntilParser::default();
+        assert_eq!(parser.parse_at("until 14:30", at(14, 0)), Ok(mins(30)));
+        assert_eq!(
+            parser.parse_at("14:30:30", at(14, 0)),
+            Ok(mins(30) + Duration::from_secs(30))
+        );
+        assert_eq!(parser.parse_at("Until 2:30 PM", at(14, 0)), Ok(mins(30)));
+        assert_eq!(parser.parse_at("till 3pm", at(14, 0)), Ok(mins(60)));
+        assert_eq!(parser.parse_at("12am", at(23, 0)), Ok(mins(60)));
+    }
+
+    #[test]
+    fn it_should_wrap_to_tomorrow() {
+        let parser = UntilParser::default();
+        assert_eq!(parser.parse_at("until 8:00", at(9, 0)), Ok(mins(23 * 60)));
+        assert_eq!(parser.parse_at("until 9:00", at(9, 0)), Ok(mins(24 * 60)));
+    }
+
+    #[test]
+    fn it_should_parse_named_times() {
+        let parser = UntilParser::default().anchor("Lunch", at(12, 30));
+        assert_eq!(parser.parse_at("until noon", at(11, 15)), Ok(mins(45)));
+        assert_eq!(parser.parse_at("until lunch", at(12, 0)), Ok(mins(30)));
+        assert_eq!(parser.parse_at("midnight", at(23, 30)), Ok(mins(30)));
+    }
+
+    #[test]
+    fn it_should_parse_shifted_times() {
+        let parser = UntilParser::default();
+        assert_eq!(parser.parse_at("+45m after lunch", at(12, 0)), Ok(mins(45)));
+        assert_eq!(
+            parser.parse_at("until 15m before 14:00", at(13, 0)),
+            Ok(mins(45))
+        );
+        assert_eq!(parser.parse_at("+45m", at(13, 0)), Ok(mins(45)));
+    }
+
+    #[test]
+    fn it_should_parse_goals() {
+        let parser = UntilParser::default();
+        assert_eq!(parser.parse_goal("25m"), Ok(Goal::Duration(mins(25))));
+        assert_eq!(
+            parser.parse_goal("14:30"),
+            Ok(Goal::Duration(mins(14) + Duration::from_secs(30)))
+        );
+        assert_eq!(parser.parse_goal("until noon"), Ok(Goal::Until(at(12, 0))));
+        assert!(matches!(
+            parser.parse_goal("25x"),
+            Err(UntilParseError::Duration(_))
+        ));
+        assert_eq!(
+            parser.parse_goal("until teatime"),
+            Err(UntilParseError::UnknownTime {
+                time: "teatime".into()
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_convert_goals_when_phases_start() {
+        let goal = Goal::Until(at(14, 30));
+        assert_eq!(goal.duration_at(at(14, 0)), mins(30));
+        assert_eq!(goal.duration_at(at(14, 10)), mins(20));
+        assert_eq!(goal.duration_at(at(14, 30)), mins(24 * 60));
+        assert_eq!(Goal::Duration(mins(25)).duration_at(at(14, 0)), mins(25));
+    }
+
+    #[test]
+    fn it_should_report_errors() {
+        let parser = UntilParser::default();
+        assert_eq!(
+            parser.parse_at("until ", at(0, 0)),
+            Err(UntilParseError::Empty)
+        );
+        assert_eq!(
+            parser.parse_at("until teatime", at(0, 0)),
+            Err(UntilParseError::UnknownTime {
+                time: "teatime".into()
+            })
+        );
+        assert_eq!(
+            parser.parse_at("until 25:00", at(0, 0)),
+            Err(UntilParseError::UnknownTime {
+                time: "25:00".into()
+            })
+        );
+        assert_eq!(
+            parser.parse_at("until 13pm", at(0, 0)),
+            Err(UntilParseError::UnknownTime {
+                time: "13pm".into()
+            })
+        );
+        assert_eq!(
+            parser.parse_at("until 14", at(0, 0)),
+            Err(UntilParseError::UnknownTime { time: "14".into() })
+        );
+        assert!(matches!(
+            parser.parse_at("+4x after noon", at(0, 0)),
+            Err(UntilParseError::Duration(_))
+        ));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{PomoCommand, PomoMessage, PomoSnapshot, Task};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Version of the wire protocol
+/// bumped whenever a change breaks older peers
+pub const WIRE_VERSION: u32 = 1;
+
+/// Everything frontends and servers send each other
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WireMessage<TTask>
+where
+    TTask: Task,
+{
+    Command(PomoCommand<TTask>),
+    Message(PomoMessage<TTask>),
+    Snapshot(PomoSnapshot<TTask>),
+}
+
+/// The version always comes first
+/// so it can be checked before the payload is decoded
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    payload: T,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WireError {
+    Encode(String),
+    Decode(String),
+    /// the peer speaks a different version
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for WireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Encode(err) => write!(f, "Unable to encode message: {}", err),
+            Self::Decode(err) => write!(f, "Unable to decode message: {}", err),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported wire version {}", version)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WireError {}
+
+/// Json for text channels and debugging,
+/// binary for compact messages
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum WireFormat {
+    #[default]
+    Json,
+    Binary,
+}
+
+impl WireFormat {
+    pub fn encode<TTask>(&self, message: &WireMessage<TTask>) -> Result<Vec<u8>, WireError>
+    where
+        TTask: Task + Serialize,
+    {
+        let envelope = Envelope {
+            version: WIRE_VERSION,
+            payload: message,
+        };
+        match self {
+            Self::Json => {
+                serde_json::to_vec(&envelope).map_err(|err| WireError::Encode(err.to_string()))
+            }
+            Self::Binary => {
+                bincode::serialize(&envelope).map_err(|err| WireError::Encode(err.to_string()))
+            }
+        }
+    }
+
+    pub fn decode<TTask>(&self, bytes: &[u8]) -> Result<WireMessage<TTask>, WireError>
+    where
+        TTask: Task + DeserializeOwned,
+    {
+        let header: Header = self.deserialize(bytes)?;
+        if header.version != WIRE_VERSION {
+            return Err(WireError::UnsupportedVersion(header.version));
+        }
+        let envelope: Envelope<WireMessage<TTask>> = self.deserialize(bytes)?;
+        Ok(envelope.payload)
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, WireError> {
+        match self {
+            Self::Json => {
+                serde_json::from_slice(bytes).map_err(|err| WireError::Decode(err.to_string()))
+            }
+            Self::Binary => {
+                bincode::deserialize(bytes).map_err(|err| WireError::Decode(err.to_string()))
+            }
+        }
+    }
+}
+
+impl<TTask> WireMessage<TTask>
+where
+    TTask: Task + Serialize + DeserializeOwned,
+{
+    /// encodes the message as json text
+    pub fn to_json(&self) -> Result<String, WireError> {
+        serde_json::to_string(&Envelope {
+            version: WIRE_VERSION,
+            payload: self,
+        })
+        .map_err(|err| WireError::Encode(err.to_string()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, WireError> {
+        WireFormat::Json.decode(text.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClockJump, PomoState, ResumePolicy, SimpleTask, TimerSnapshot, Transition};
+    use std::time::Duration;
+
+    fn messages() -> Vec<WireMessage<SimpleTask>> {
+        vec![
+            WireMessage::Command(PomoCommand::AddTask(SimpleTask::new("a"))),
+            WireMessage::Command(PomoCommand::SkipTo(PomoState::LongBreak)),
+            WireMessage::Message(PomoMessage::Transition(Transition::new_task(
+                PomoState::Working,
+                PomoState::Break,
+                SimpleTask::new("b"),
+            ))),
+            WireMessage::Message(PomoMessage::ClockJump(ClockJump {
+                gap: Duration::from_secs(90),
+                policy: ResumePolicy::Pause,
+                transition: None,
+            })),
+            WireMessage::Message(PomoMessage::Reset),
+            WireMessage::Snapshot(PomoSnapshot {
+                state: PomoState::Working,
+                prev_state: PomoState::Pending,
+                tasks: vec![SimpleTask::new("c")],
+                current_cycles: 1,
+                cycles_until_long_break: 4,
+                total_cycles: 8,
+                timer: Some(TimerSnapshot {
+                    elapsed: Duration::from_millis(1500),
+                    goal: Duration::from_secs(60 * 25),
+                    paused: false,
+                }),
+            }),
+        ]
+    }
+
+    #[test]
+    fn it_should_round_trip_messages() {
+        for format in [WireFormat::Json, WireFormat::Binary] {
+            for message in messages() {
+                let bytes = format.encode(&message).unwrap();
+                assert_eq!(format.decode::<SimpleTask>(&bytes), Ok(message));
+            }
+        }
+
+        for message in messages() {
+            let text = message.to_json().unwrap();
+            assert_eq!(WireMessage::from_json(&text), Ok(message));
+        }
+    }
+
+    #[test]
+    fn it_should_reject_other_versions() {
+        let text = "{\"version\": 2, \"payload\": {\"Command\": \"Start\"}}";
+        assert_eq!(
+            WireMessage::<SimpleTask>::from_json(text),
+            Err(WireError::UnsupportedVersion(2))
+        );
+
+        let bytes = bincode::serialize(&Envelope {
+            version: 0,
+            payload: (),
+        })
+        .unwrap();
+        assert_eq!(
+            WireFormat::Binary.decode::<SimpleTask>(&bytes),
+            Err(WireError::UnsupportedVersion(0))
+        );
+
+        assert!(matches!(
+            WireFormat::Json.decode::<SimpleTask>(b"{}"),
+            Err(WireError::Decode(_))
+        ));
+    }
+}
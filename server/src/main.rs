@@ -0,0 +1,80 @@
+mod session;
+
+use clap::Parser;
+use pomododragon::{
+    PomoRunner, ResumePolicy, SimplePomoBuilder, SimpleTask, SystemTimer, TimeParser,
+};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio_stream::StreamExt;
+
+/// Hosts a shared pomodoro session
+/// clients join with ws://<address>
+#[derive(Parser, Debug)]
+#[clap(version = "0.1.0", author = "Lukas Krickl <lukas@krickl.dev>")]
+struct Opts {
+    #[clap(short, long, default_value = "127.0.0.1:9090")]
+    address: SocketAddr,
+
+    #[clap(short, long, default_value = "5m", parse(try_from_str = TimeParser::parse))]
+    break_time: Duration,
+    #[clap(short, long, default_value = "25m", parse(try_from_str = TimeParser::parse))]
+    work_time: Duration,
+    #[clap(short, long, default_value = "30m", parse(try_from_str = TimeParser::parse))]
+    long_break_time: Duration,
+
+    /// what to do when the system resumes from suspend
+    /// during a phase: complete, pause or continue
+    #[clap(short, long, default_value = "continue")]
+    on_resume: ResumePolicy,
+
+    #[clap(short, long, default_value = "4")]
+    until_break: usize,
+    #[clap(short, long, default_value = "8")]
+    total: usize,
+}
+
+#[tokio::main]
+async fn main() {
+    let opts: Opts = Opts::parse();
+
+    let pomo = SimplePomoBuilder::<SimpleTask, SystemTimer>::default()
+        .break_timer(SystemTimer::new(opts.break_time))
+        .work_timer(SystemTimer::new(opts.work_time))
+        .long_break_timer(SystemTimer::new(opts.long_break_time))
+        .cycles_until_long_break(opts.until_break)
+        .total_cycles(opts.total)
+        .resume_policy(opts.on_resume)
+        .build()
+        .expect("Unable to build pomo");
+
+    let (snapshots_tx, snapshots) = watch::channel(pomo.snapshot());
+    let (runner, commands, mut messages) = PomoRunner::new(pomo);
+    tokio::spawn(runner.run_with(move |pomo, _| {
+        snapshots_tx.send_replace(pomo.snapshot());
+    }));
+
+    // the runner stops once nobody listens to its messages
+    tokio::spawn(async move {
+        while let Some(message) = messages.next().await {
+            println!("{}", message);
+        }
+    });
+
+    let listener = TcpListener::bind(opts.address)
+        .await
+        .expect("Unable to bind address");
+    println!("Listening on ws://{}", opts.address);
+
+    while let Ok((stream, address)) = listener.accept().await {
+        let commands = commands.clone();
+        let snapshots = snapshots.clone();
+        tokio::spawn(async move {
+            if let Err(err) = session::serve(stream, commands, snapshots).await {
+                eprintln!("{}: {}", address, err);
+            }
+        });
+    }
+}
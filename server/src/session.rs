@@ -0,0 +1,131 @@
+use futures_util::{SinkExt, StreamExt};
+use pomododragon::{PomoSender, PomoSnapshot, SimpleTask, WireFormat, WireMessage};
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::{Error, Message};
+
+pub type SnapshotReceiver = watch::Receiver<PomoSnapshot<SimpleTask>>;
+
+/// Serves a single client.
+/// The client receives the current snapshot right away and after every change,
+/// commands sent by the client are forwarded to the shared machine.
+/// Snapshots are sent in the wire format the client last used
+pub async fn serve(
+    stream: TcpStream,
+    commands: PomoSender<SimpleTask>,
+    mut snapshots: SnapshotReceiver,
+) -> Result<(), Error> {
+    let mut socket = tokio_tungstenite::accept_async(stream).await?;
+    let mut format = WireFormat::Json;
+
+    let snapshot = snapshots.borrow_and_update().clone();
+    socket.send(encode(format, snapshot)).await?;
+
+    loop {
+        tokio::select! {
+            message = socket.next() => match message {
+                Some(Ok(message @ (Message::Text(_) | Message::Binary(_)))) => {
+                    format = if message.is_binary() {
+                        WireFormat::Binary
+                    } else {
+                        WireFormat::Json
+                    };
+                    match format.decode::<SimpleTask>(&message.into_data()) {
+                        Ok(WireMessage::Command(command)) => {
+                            if commands.send(command).is_err() {
+                                // the session is over
+                                break;
+                            }
+                        }
+                        Ok(message) => eprintln!("Unexpected message: {:?}", message),
+                        Err(err) => eprintln!("{}", err),
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => (),
+                Some(Err(err)) => return Err(err),
+            },
+            changed = snapshots.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let snapshot = snapshots.borrow_and_update().clone();
+                socket.send(encode(format, snapshot)).await?;
+            }
+        }
+    }
+
+    socket.close(None).await.or_else(|err| match err {
+        Error::ConnectionClosed | Error::AlreadyClosed => Ok(()),
+        err => Err(err),
+    })
+}
+
+fn encode(format: WireFormat, snapshot: PomoSnapshot<SimpleTask>) -> Message {
+    let bytes = format
+        .encode(&WireMessage::Snapshot(snapshot))
+        .unwrap_or_default();
+    match format {
+        WireFormat::Json => Message::text(String::from_utf8(bytes).unwrap_or_default()),
+        WireFormat::Binary => Message::binary(bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pomododragon::{InstantTimer, PomoCommand, PomoRunner, PomoState, SimplePomo};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    #[tokio::test]
+    async fn it_should_sync_clients() {
+        let pomo = SimplePomo::<SimpleTask, InstantTimer>::default();
+        let (snapshots_tx, snapshots) = watch::channel(pomo.snapshot());
+        let (runner, commands, _messages) = PomoRunner::new(pomo);
+        tokio::spawn(runner.run_with(move |pomo, _| {
+            snapshots_tx.send_replace(pomo.snapshot());
+        }));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, commands.clone(), snapshots.clone()));
+            }
+        });
+
+        let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        assert_eq!(
+            next_snapshot(&mut client).await.state,
+            PomoState::NotStarted
+        );
+
+        let start = WireFormat::Binary
+            .encode(&WireMessage::<SimpleTask>::Command(PomoCommand::Start))
+            .unwrap();
+        client.send(Message::binary(start)).await.unwrap();
+
+        let mut snapshot = next_snapshot(&mut client).await;
+        while snapshot.state != PomoState::Working {
+            snapshot = next_snapshot(&mut client).await;
+        }
+        assert_eq!(snapshot.timer.unwrap().goal.as_secs(), 60 * 25);
+    }
+
+    async fn next_snapshot(client: &mut Client) -> PomoSnapshot<SimpleTask> {
+        loop {
+            let message = client.next().await.unwrap().unwrap();
+            let format = if message.is_binary() {
+                WireFormat::Binary
+            } else {
+                WireFormat::Json
+            };
+            if let Ok(WireMessage::Snapshot(snapshot)) = format.decode(&message.into_data()) {
+                return snapshot;
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::error::Error;
+use pomododragon::PomoState;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::js_sys::Reflect;
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    AudioContext, Notification, NotificationOptions, NotificationPermission, OscillatorType,
+    ServiceWorkerRegistration,
+};
+
+/// Alarm sounds are synthesized with web audio
+/// so no sound files have to be shipped
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlarmSound {
+    Beep,
+    Chime,
+    Bell,
+}
+
+impl AlarmSound {
+    pub const ALL: [Self; 3] = [Self::Beep, Self::Chime, Self::Bell];
+
+    /// frequency, start and length in seconds of every note
+    fn notes(&self) -> &'static [(f32, f64, f64)] {
+        match self {
+            Self::Beep => &[(880.0, 0.0, 0.15), (880.0, 0.25, 0.15), (880.0, 0.5, 0.15)],
+            Self::Chime => &[(659.25, 0.0, 0.4), (783.99, 0.2, 0.4), (1046.5, 0.4, 0.8)],
+            Self::Bell => &[(523.25, 0.0, 1.5), (1046.5, 0.0, 1.0)],
+        }
+    }
+
+    fn wave(&self) -> OscillatorType {
+        match self {
+            Self::Beep => OscillatorType::Square,
+            Self::Chime => OscillatorType::Sine,
+            Self::Bell => OscillatorType::Triangle,
+        }
+    }
+}
+
+impl std::fmt::Display for AlarmSound {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Beep => "beep",
+                Self::Chime => "chime",
+                Self::Bell => "bell",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for AlarmSound {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|sound| sound.to_string() == s)
+            .ok_or_else(|| format!("Unknown alarm sound '{}'", s))
+    }
+}
+
+/// Announces the end of a phase
+#[derive(Default)]
+pub struct Alarm {
+    context: Option<AudioContext>,
+}
+
+impl Alarm {
+    /// Browsers only play audio after the user interacted with the page,
+    /// this has to be called from an event handler before the first alarm
+    pub fn unlock(&mut self) -> Result<(), Error> {
+        match &self.context {
+            Some(context) => {
+                let _ = context.resume();
+            }
+            None => self.context = Some(AudioContext::new().map_err(|_| Error::Audio)?),
+        }
+        Ok(())
+    }
+
+    pub fn play(&mut self, sound: AlarmSound) -> Result<(), Error> {
+        self.unlock()?;
+        let context = self.context.as_ref().ok_or(Error::Audio)?;
+
+        let now = context.current_time();
+        for (frequency, start, length) in sound.notes() {
+            Self::note(context, sound.wave(), *frequency, now + start, *length)
+                .map_err(|_| Error::Audio)?;
+        }
+        Ok(())
+    }
+
+    fn note(
+        context: &AudioContext,
+        wave: OscillatorType,
+        frequency: f32,
+        start: f64,
+        length: f64,
+    ) -> Result<(), JsValue> {
+        let oscillator = context.create_oscillator()?;
+        oscillator.set_type(wave);
+        oscillator.frequency().set_value(frequency);
+
+        // fade out instead of clicking
+        let gain = context.create_gain()?;
+        gain.gain().set_value_at_time(0.3, start)?;
+        gain.gain()
+            .exponential_ramp_to_value_at_time(0.001, start + length)?;
+
+        oscillator.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&context.destination())?;
+        oscillator.start_with_when(start)?;
+        oscillator.stop_with_when(start + length)?;
+        Ok(())
+    }
+
+    /// Shows a notification for the phase that just started.
+    /// The service worker shows it if there is one,
+    /// some platforms only allow notifications from service workers
+    pub fn notify(state: PomoState) -> Result<(), Error> {
+        if Notification::permission() != NotificationPermission::Granted {
+            return Err(Error::Notification);
+        }
+
+        let options = NotificationOptions::new();
+        options.set_body(match state {
+            PomoState::Working => "Back to work",
+            PomoState::Break => "Time for a short break",
+            PomoState::LongBreak => "Time for a long break",
+            _ => "All cycles completed",
+        });
+
+        let navigator = gloo::utils::window().navigator();
+        let has_worker = Reflect::has(&navigator, &"serviceWorker".into()).unwrap_or(false)
+            && navigator.service_worker().controller().is_some();
+        if !has_worker {
+            return Notification::new_with_options("PomodoDragon", &options)
+                .map(|_| ())
+                .map_err(|_| Error::Notification);
+        }
+
+        spawn_local(async move {
+            let shown = match navigator.service_worker().ready() {
+                Ok(ready) => match JsFuture::from(ready).await {
+                    Ok(registration) => registration
+                        .unchecked_into::<ServiceWorkerRegistration>()
+                        .show_notification_with_options("PomodoDragon", &options)
+                        .map(|_| ()),
+                    Err(err) => Err(err),
+                },
+                Err(err) => Err(err),
+            };
+            if shown.is_err() {
+                log::error!("{}", Error::Notification);
+            }
+        });
+        Ok(())
+    }
+
+    /// asks the user to allow notifications
+    /// and returns whether they did
+    pub async fn request_permission() -> bool {
+        match Notification::request_permission() {
+            Ok(promise) => {
+                let permission = JsFuture::from(promise).await.ok();
+                permission.and_then(|permission| permission.as_string()) == Some("granted".into())
+            }
+            Err(_) => false,
+        }
+    }
+}
@@ -1,23 +1,38 @@
+use crate::alarm::{Alarm, AlarmSound};
 use crate::bottombar::BottomBar;
 use crate::error::Error;
+use crate::help::view_help;
 use crate::icon::Icon;
 use crate::input::{Input, InputKind};
-use gloo::storage::{LocalStorage, Storage};
-use gloo_timers::callback::Interval;
+use crate::preferences::Preferences;
+use crate::presets::PresetSettings;
+use crate::router::AppRoute;
+use crate::session::Session;
+use crate::settings::{
+    AlarmSettings, SettingInput, ShortcutSettings, ThemeSettings, TimerSettings,
+};
+use crate::shortcuts::{Action, Shortcuts};
+use crate::stats::view_stats;
+use crate::store::LocalStorageStore;
+use crate::theme::{Accents, Theme, ThemeSheets};
+use crate::ticker::{Ticker, TickerInput};
+use crate::toast::{view_toasts, Toast};
+use chrono::Utc;
+use gloo::events::EventListener;
+use gloo::file::callbacks::FileReader;
+use gloo::file::{Blob, ObjectUrl};
+use gloo_timers::callback::Timeout;
 use pomododragon::{
-    Actor, InstantTimer, Pomo, PomoActions, PomoCommand, PomoData, PomoMessage, PomoState,
-    SimplePomo, SimpleTask, TimeParser, Timer,
+    Actor, Goal, HistoryEntry, HistoryRecorder, InstantTimer, ObservedPomo, PomoCommand, PomoData,
+    PomoMessage, PomoObserver, PomoSnapshot, PomoState, Preset, SavedSession, Settings, SimplePomo,
+    SimpleTask, Store, StoreData, Task, TaskFormat, TimeParser, Timer, Transition, UntilParser,
 };
+use std::collections::HashMap;
 use std::time::Duration;
+use web_sys::wasm_bindgen::JsCast;
 use yew::prelude::*;
-
-// keys for local storage
-const WORK_TIME_KEY: &str = "pomododragon.work_timer";
-const BREAK_TIME_KEY: &str = "pomododragon.break_time";
-const LONG_BREAK_TIME_KEY: &str = "pomododragon.long_break_time";
-const TOTAL_CYCLES_KEY: &str = "pomododragon.total_cycles";
-const CYCLES_UNTIL_BREAK_KEY: &str = "pomododragon.cycles_until_break";
-const TASKS_KEY: &str = "pomododragon.tasks";
+use yew_agent::{Bridge, Bridged};
+use yew_router::prelude::*;
 
 pub enum Msg {
     Start,
@@ -26,15 +41,51 @@ pub enum Msg {
     Resume,
     Add,
     Delete(usize),
+    Complete(usize),
+    Reopen(usize),
+    Edit(usize),
+    UpdateEdit(String),
+    SaveEdit,
+    DragStart(usize),
+    Drop(usize),
     Update(String),
     UpdateWorkTime(String),
     UpdateShortBreakTime(String),
     UpdateLongBreakTime(String),
+    NormalizeSettings,
+    ApplyPreset(Preset),
+    UpdatePresetName(String),
+    SavePreset,
+    DeletePreset(String),
+    ImportPresets(Option<web_sys::File>),
+    PresetsRead(String),
+    ExportPresets,
+    KeepLinkSettings,
     UpdateUntilLongBreak(String),
     UpdateTotalCycles(String),
-    PomoMessage(PomoMessage<SimpleTask>),
+    StoreTasks,
+    Record(HistoryEntry),
+    ImportTasks(Option<web_sys::File>),
+    TasksRead(TaskFormat, String),
+    ExportTasks(TaskFormat),
+    UpdateSessionUrl(String),
+    Join,
+    Leave,
+    Snapshot(PomoSnapshot<SimpleTask>),
+    PhaseEnded(PomoState),
+    ToggleNotifications,
+    NotificationsAllowed(bool),
+    SelectAlarm(Option<AlarmSound>),
+    TestAlarm,
     SkipTo(PomoState),
+    Shortcut(Action),
+    UpdateShortcut(Action, String),
+    CloseHelp,
+    SelectTheme(Theme),
+    UpdateAccent(PomoState, String),
+    ResetAccents,
     Error(Error),
+    DismissToast(usize),
     SetTab(TabState),
     Tick,
 }
@@ -42,7 +93,7 @@ pub enum Msg {
 pub struct App {
     // `ComponentLink` is like a reference to a component.
     // It can be used to send messages to the component
-    pomo: SimplePomo<SimpleTask, InstantTimer>,
+    pomo: ObservedPomo<SimpleTask, SimplePomo<SimpleTask, InstantTimer>>,
     description_buffer: String,
     work_time_buffer: String,
     until_long_break_buffer: String,
@@ -52,100 +103,370 @@ pub struct App {
     short_break_time_buffer: String,
     long_break_time_buffer: String,
     state: TabState,
-    _task: Interval,
+    store: LocalStorageStore,
+    data: StoreData,
+    preferences: Preferences,
+    reader: Option<FileReader>,
+    session: Option<Session>,
+    session_url_buffer: String,
+    preset_name_buffer: String,
+    /// the user's own settings while the settings of a link are used,
+    /// they are stored instead of the link's settings
+    own_settings: Option<Settings>,
+    alarm: Alarm,
+    ticker: Box<dyn Bridge<Ticker>>,
+    _visibility: EventListener,
+    _shortcuts: EventListener,
+    show_help: bool,
+    task_input: NodeRef,
+    themes: ThemeSheets,
+    setting_errors: HashMap<Setting, String>,
+    toasts: Vec<Toast>,
+    next_toast: usize,
+    /// focused once it is rendered
+    focus: Option<NodeRef>,
+    editing: Option<EditedTask>,
+    edit_input: NodeRef,
+    /// index and description of the task that is dragged
+    dragging: Option<(usize, String)>,
+}
+
+/// A task that is edited, the task list may change in the meantime,
+/// e.g. when a work phase completes the first task
+struct EditedTask {
+    index: usize,
+    /// the description before editing to find the task again
+    original: String,
+    description: String,
+}
+
+/// Stores the tasks whenever the pomo machine transitions
+/// since a transition may complete a task
+struct TaskStoreObserver {
+    store_tasks: Callback<()>,
+}
+
+impl PomoObserver<SimpleTask> for TaskStoreObserver {
+    fn on_transition(&mut self, _transition: &Transition<SimpleTask>) {
+        self.store_tasks.emit(());
+    }
+}
+
+/// Reports phases that ran out, skipped phases are not reported
+struct AlarmObserver {
+    phase_ended: Callback<PomoState>,
+}
+
+impl PomoObserver<SimpleTask> for AlarmObserver {
+    fn on_transition(&mut self, transition: &Transition<SimpleTask>) {
+        let ran_out = matches!(
+            transition.from,
+            PomoState::Working | PomoState::Break | PomoState::LongBreak
+        ) && transition.to != PomoState::Paused;
+        if ran_out && !transition.skipped {
+            self.phase_ended.emit(transition.to);
+        }
+    }
+}
+
+/// The settings that are validated before they are applied
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Setting {
+    WorkTime,
+    ShortBreakTime,
+    LongBreakTime,
+    UntilLongBreak,
+    TotalCycles,
 }
 
-#[derive(PartialEq, Eq)]
+/// updates a setting from its input
+type SettingMsg = fn(String) -> Msg;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TabState {
     Timer,
     Tasks,
+    Stats,
     Settings,
 }
 
+impl TabState {
+    fn route(&self) -> AppRoute {
+        match self {
+            Self::Timer => AppRoute::Timer,
+            Self::Tasks => AppRoute::Tasks,
+            Self::Stats => AppRoute::Stats,
+            Self::Settings => AppRoute::Settings,
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AppProps {
+    #[prop_or(TabState::Timer)]
+    pub tab: TabState,
+}
+
+/// timers that run out right away are rejected
+fn timer_goal<E: std::fmt::Display>(goal: Result<Duration, E>) -> Result<Duration, String> {
+    match goal {
+        Ok(goal) if goal.is_zero() => Err("Must be longer than 0 seconds".into()),
+        Ok(goal) => Ok(goal),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn cycles(value: &str) -> Result<usize, String> {
+    match value.trim().parse::<usize>() {
+        Ok(0) | Err(_) => Err("Must be a whole number of at least 1".into()),
+        Ok(cycles) => Ok(cycles),
+    }
+}
+
+/// how long a download's object url is kept in milliseconds
+const DOWNLOAD_URL_TIME: u32 = 60_000;
+
+/// offers text content as a file download
+fn download(file_name: &str, content: &str) {
+    let url = ObjectUrl::from(Blob::new_with_options(content, Some("text/plain")));
+    let anchor = gloo::utils::document()
+        .create_element("a")
+        .ok()
+        .and_then(|element| element.dyn_into::<web_sys::HtmlAnchorElement>().ok());
+
+    if let Some(anchor) = anchor {
+        anchor.set_href(&url);
+        anchor.set_download(file_name);
+        anchor.click();
+    }
+
+    // the browser may read the url after the click returned,
+    // so it is only revoked a while later
+    Timeout::new(DOWNLOAD_URL_TIME, move || drop(url)).forget();
+}
+
 impl Component for App {
     type Message = Msg;
-    type Properties = ();
+    type Properties = AppProps;
 
     fn create(ctx: &Context<Self>) -> Self {
-        let pomo = SimplePomo::default();
+        let store = LocalStorageStore;
+        let mut data = store.load().unwrap_or_else(|err| {
+            log::error!("{}", err);
+            StoreData::default()
+        });
+
+        let mut pomo = ObservedPomo::new(SimplePomo::default());
+        data.settings.apply(pomo.pomo_mut());
+        let record = ctx.link().callback(Msg::Record);
+        pomo.subscribe(Box::new(HistoryRecorder::new(Box::new(move |entry| {
+            record.emit(entry)
+        }))));
+        match data.session.take() {
+            // continue where the last visit left off
+            Some(session) => {
+                for message in session.restore(pomo.pomo_mut(), Utc::now()) {
+                    // only the history learns about the restored phases,
+                    // the alarm would ring for phases that ended long ago
+                    pomo.notify(&message);
+                    if let PomoMessage::Transition(Transition {
+                        completed: Some(task),
+                        ..
+                    }) = message
+                    {
+                        data.tasks.push(task);
+                    }
+                }
+            }
+            None => {
+                for task in data.pending_tasks() {
+                    pomo.execute(PomoCommand::AddTask(task));
+                }
+            }
+        }
+
+        pomo.subscribe(Box::new(TaskStoreObserver {
+            store_tasks: ctx.link().callback(|_| Msg::StoreTasks),
+        }));
+        pomo.subscribe(Box::new(AlarmObserver {
+            phase_ended: ctx.link().callback(Msg::PhaseEnded),
+        }));
         let link = ctx.link().clone();
+        let preferences = Preferences::load();
+        let shortcuts =
+            Shortcuts::new(&preferences.shortcuts).listen(ctx.link().callback(Msg::Shortcut));
+        let mut ticker = Ticker::bridge(ctx.link().callback(|_| Msg::Tick));
+        ticker.send(TickerInput::Every(200));
 
         let mut n = Self {
             pomo,
             description_buffer: "".into(),
-            work_time_buffer: LocalStorage::get(WORK_TIME_KEY).unwrap_or_else(|_| "25".into()),
-            short_break_time_buffer: LocalStorage::get(BREAK_TIME_KEY)
-                .unwrap_or_else(|_| "5".into()),
-            long_break_time_buffer: LocalStorage::get(LONG_BREAK_TIME_KEY)
-                .unwrap_or_else(|_| "30".into()),
+            work_time_buffer: TimeParser::format(data.settings.work_time),
+            short_break_time_buffer: TimeParser::format(data.settings.short_break_time),
+            long_break_time_buffer: TimeParser::format(data.settings.long_break_time),
             progress: "0".into(),
             goal: "100".into(),
-            until_long_break_buffer: LocalStorage::get(CYCLES_UNTIL_BREAK_KEY)
-                .unwrap_or_else(|_| "4".into()),
-            total_cycles_buffer: LocalStorage::get(TOTAL_CYCLES_KEY).unwrap_or_else(|_| "8".into()),
-            state: TabState::Timer,
-            _task: Interval::new(200, move || {
-                link.send_message(Msg::Tick);
-            }),
+            until_long_break_buffer: data.settings.cycles_until_long_break.to_string(),
+            total_cycles_buffer: data.settings.total_cycles.to_string(),
+            state: ctx.props().tab,
+            store,
+            data,
+            preferences,
+            reader: None,
+            session: None,
+            session_url_buffer: "".into(),
+            preset_name_buffer: "".into(),
+            own_settings: None,
+            alarm: Alarm::default(),
+            ticker,
+            // catch up as soon as a background tab is shown again
+            _visibility: EventListener::new(
+                &gloo::utils::document(),
+                "visibilitychange",
+                move |_| link.send_message(Msg::Tick),
+            ),
+            _shortcuts: shortcuts,
+            show_help: false,
+            task_input: NodeRef::default(),
+            focus: None,
+            editing: None,
+            edit_input: NodeRef::default(),
+            dragging: None,
+            themes: ThemeSheets::load(),
+            setting_errors: HashMap::new(),
+            toasts: vec![],
+            next_toast: 0,
         };
+        n.themes.apply(n.preferences.theme);
+        n.schedule_deadline();
 
-        n.update(ctx, Msg::UpdateWorkTime(n.work_time_buffer.clone()));
-        n.update(
-            ctx,
-            Msg::UpdateShortBreakTime(n.short_break_time_buffer.clone()),
-        );
-        n.update(
-            ctx,
-            Msg::UpdateLongBreakTime(n.long_break_time_buffer.clone()),
-        );
-        n.update(ctx, Msg::UpdateTotalCycles(n.total_cycles_buffer.clone()));
-        n.update(
-            ctx,
-            Msg::UpdateUntilLongBreak(n.until_long_break_buffer.clone()),
-        );
-
-        let tasks: Vec<String> = LocalStorage::get(TASKS_KEY).unwrap_or_else(|_| vec![]);
-        for task in tasks {
-            // this usually will not fail!
-            n.pomo.execute(PomoCommand::AddTask(SimpleTask::new(&task)));
-        }
-
+        n.apply_query(ctx);
         n
     }
 
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        self.state = ctx.props().tab;
+        true
+    }
+
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::Start => {
-                self.pomo.start();
+                // absolute work times are relative to the start
+                self.update(ctx, Msg::UpdateWorkTime(self.work_time_buffer.clone()));
+                if !self.setting_errors.is_empty() {
+                    return self.update(ctx, Msg::Error(Error::InvalidSettings));
+                }
+                if let Err(err) = self.alarm.unlock() {
+                    self.update(ctx, Msg::Error(err));
+                }
+                self.command(ctx, PomoCommand::Start);
                 true
             }
             Msg::Pause => {
-                self.pomo.pause();
+                self.command(ctx, PomoCommand::Pause);
                 true
             }
             Msg::Resume => {
-                self.pomo.unpause();
+                self.command(ctx, PomoCommand::Unpause);
                 true
             }
             Msg::Stop => {
-                self.pomo.reset();
+                self.command(ctx, PomoCommand::Reset);
                 true
             }
             Msg::Add => {
                 if !self.description_buffer.is_empty() {
-                    self.pomo.execute(PomoCommand::AddTask(SimpleTask::new(
-                        &self.description_buffer,
-                    )));
+                    self.command(
+                        ctx,
+                        PomoCommand::AddTask(SimpleTask::new(&self.description_buffer)),
+                    );
 
-                    self.store_tasks(ctx);
+                    self.store_data(ctx);
 
                     self.description_buffer = "".into();
                 }
                 true
             }
             Msg::Delete(index) => {
-                self.pomo.execute(PomoCommand::RemoveTask(index));
-                self.store_tasks(ctx);
+                self.command(ctx, PomoCommand::RemoveTask(index));
+                self.store_data(ctx);
+                true
+            }
+            Msg::Complete(index) => {
+                if let Some(mut task) = self.pomo.tasks().get(index).cloned() {
+                    task.complete();
+                    // completed tasks are kept locally, also those of a shared session
+                    self.data.tasks.push(task);
+                    self.command(ctx, PomoCommand::RemoveTask(index));
+                    if self.session.is_some() {
+                        self.store_data(ctx);
+                    }
+                }
+                true
+            }
+            Msg::Reopen(index) => {
+                if index < self.data.tasks.len() {
+                    let task = self.data.tasks.remove(index);
+                    self.command(
+                        ctx,
+                        PomoCommand::AddTask(SimpleTask::new(task.description())),
+                    );
+                    if self.session.is_some() {
+                        self.store_data(ctx);
+                    }
+                }
+                true
+            }
+            Msg::Edit(index) => {
+                if let Some(task) = self.pomo.tasks().get(index) {
+                    self.editing = Some(EditedTask {
+                        index,
+                        original: task.description().into(),
+                        description: task.description().into(),
+                    });
+                    self.focus = Some(self.edit_input.clone());
+                }
+                true
+            }
+            Msg::UpdateEdit(value) => {
+                if let Some(editing) = &mut self.editing {
+                    editing.description = value;
+                }
+                false
+            }
+            Msg::SaveEdit => {
+                if let Some(editing) = self.editing.take() {
+                    let description = editing.description.trim();
+                    // the task may have been completed or removed while editing
+                    if let Some(index) = self.find_task(editing.index, &editing.original) {
+                        if !description.is_empty() {
+                            self.command(
+                                ctx,
+                                PomoCommand::UpdateTask(index, SimpleTask::new(description)),
+                            );
+                        }
+                    }
+                }
+                true
+            }
+            Msg::DragStart(index) => {
+                self.dragging = self
+                    .pomo
+                    .tasks()
+                    .get(index)
+                    .map(|task| (index, task.description().into()));
+                false
+            }
+            Msg::Drop(index) => {
+                if let Some((from, description)) = self.dragging.take() {
+                    match self.find_task(from, &description) {
+                        Some(from) if from != index && index < self.pomo.tasks().len() => {
+                            self.command(ctx, PomoCommand::MoveTask(from, index));
+                        }
+                        _ => (),
+                    }
+                }
                 true
             }
             Msg::Update(value) => {
@@ -153,80 +474,362 @@ impl Component for App {
                 true
             }
             Msg::UpdateWorkTime(value) => {
-                if LocalStorage::set(WORK_TIME_KEY, value.clone()).is_err() {
-                    self.update(ctx, Msg::Error(Error::LocalStorageWrite));
-                }
+                // plain numbers are minutes, anything else is
+                // a duration or an absolute time
+                let goal = TimeParser::parse_minutes(&value)
+                    .map(Goal::Duration)
+                    .or_else(|_| UntilParser::default().parse_time(&value).map(Goal::Until))
+                    .map_err(|_| "Expected e.g. 25m, 1h30m, 90s or until 14:30");
 
                 self.work_time_buffer = value;
-                self.pomo.work_timer = InstantTimer::new(
-                    TimeParser::parse(&format!("{}m", self.work_time_buffer))
-                        .unwrap_or_else(|| Duration::from_secs(0)),
-                );
-
+                let duration = timer_goal(goal.map(|goal| goal.duration()));
+                if let (Some(duration), Ok(goal)) =
+                    (self.validate(Setting::WorkTime, duration), goal)
+                {
+                    let pomo = self.pomo.pomo_mut();
+                    match goal {
+                        // only the next work phase ends at the given time,
+                        // the stored work time stays the same
+                        Goal::Until(_) => pomo.next_work_goal = Some(goal),
+                        Goal::Duration(_) => {
+                            pomo.next_work_goal = None;
+                            pomo.work_timer = InstantTimer::new(duration);
+                            self.data.settings.work_time = duration;
+                            self.store_data(ctx);
+                        }
+                    }
+                }
                 true
             }
             Msg::UpdateShortBreakTime(value) => {
-                if LocalStorage::set(BREAK_TIME_KEY, value.clone()).is_err() {
-                    self.update(ctx, Msg::Error(Error::LocalStorageWrite));
-                }
-
+                let goal = TimeParser::parse_minutes(&value);
                 self.short_break_time_buffer = value;
-                self.pomo.break_timer = InstantTimer::new(
-                    TimeParser::parse(&format!("{}m", self.short_break_time_buffer))
-                        .unwrap_or_else(|| Duration::from_secs(0)),
-                );
+                if let Some(goal) = self.validate(Setting::ShortBreakTime, timer_goal(goal)) {
+                    self.pomo.pomo_mut().break_timer = InstantTimer::new(goal);
+                    self.data.settings.short_break_time = goal;
+                    self.store_data(ctx);
+                }
                 true
             }
             Msg::UpdateLongBreakTime(value) => {
-                if LocalStorage::set(LONG_BREAK_TIME_KEY, value.clone()).is_err() {
-                    self.update(ctx, Msg::Update("LocalStorageWriteFailed".into()));
-                }
-
+                let goal = TimeParser::parse_minutes(&value);
                 self.long_break_time_buffer = value;
-                self.pomo.long_break_timer = InstantTimer::new(
-                    TimeParser::parse(&format!("{}m", self.long_break_time_buffer))
-                        .unwrap_or_else(|| Duration::from_secs(0)),
-                );
+                if let Some(goal) = self.validate(Setting::LongBreakTime, timer_goal(goal)) {
+                    self.pomo.pomo_mut().long_break_timer = InstantTimer::new(goal);
+                    self.data.settings.long_break_time = goal;
+                    self.store_data(ctx);
+                }
                 true
             }
-            Msg::UpdateUntilLongBreak(value) => {
-                if LocalStorage::set(CYCLES_UNTIL_BREAK_KEY, value.clone()).is_err() {
-                    self.update(ctx, Msg::Error(Error::LocalStorageWrite));
+            Msg::NormalizeSettings => {
+                // absolute work times are kept as they are relative to the start
+                if TimeParser::parse_minutes(&self.work_time_buffer).is_ok()
+                    && !self.setting_errors.contains_key(&Setting::WorkTime)
+                {
+                    self.work_time_buffer = TimeParser::format(self.data.settings.work_time);
+                }
+                if !self.setting_errors.contains_key(&Setting::ShortBreakTime) {
+                    self.short_break_time_buffer =
+                        TimeParser::format(self.data.settings.short_break_time);
+                }
+                if !self.setting_errors.contains_key(&Setting::LongBreakTime) {
+                    self.long_break_time_buffer =
+                        TimeParser::format(self.data.settings.long_break_time);
+                }
+                true
+            }
+            Msg::ApplyPreset(preset) => {
+                let settings = preset.settings;
+                settings.apply(self.pomo.pomo_mut());
+                self.work_time_buffer = TimeParser::format(settings.work_time);
+                self.short_break_time_buffer = TimeParser::format(settings.short_break_time);
+                self.long_break_time_buffer = TimeParser::format(settings.long_break_time);
+                self.until_long_break_buffer = settings.cycles_until_long_break.to_string();
+                self.total_cycles_buffer = settings.total_cycles.to_string();
+                self.setting_errors.clear();
+                self.data.settings = settings;
+                self.store_data(ctx);
+                true
+            }
+            Msg::UpdatePresetName(value) => {
+                self.preset_name_buffer = value;
+                true
+            }
+            Msg::SavePreset => {
+                let name = self.preset_name_buffer.trim();
+                if !name.is_empty() {
+                    let preset = Preset::new(name, self.data.settings.clone());
+                    Preset::merge(&mut self.data.presets, vec![preset]);
+                    self.preset_name_buffer = "".into();
+                    self.store_data(ctx);
+                }
+                true
+            }
+            Msg::DeletePreset(name) => {
+                self.data.presets.retain(|preset| preset.name != name);
+                self.store_data(ctx);
+                true
+            }
+            Msg::ImportPresets(file) => {
+                if let Some(file) = file {
+                    let link = ctx.link().clone();
+                    self.reader = Some(gloo::file::callbacks::read_as_text(
+                        &file.into(),
+                        move |result| match result {
+                            Ok(input) => link.send_message(Msg::PresetsRead(input)),
+                            Err(_) => link.send_message(Msg::Error(Error::FileRead)),
+                        },
+                    ));
                 }
+                false
+            }
+            Msg::PresetsRead(input) => {
+                self.reader = None;
+                match Preset::import(&input) {
+                    Ok(presets) => {
+                        Preset::merge(&mut self.data.presets, presets);
+                        self.store_data(ctx);
+                        true
+                    }
+                    Err(err) => {
+                        log::error!("{}", err);
+                        self.update(ctx, Msg::Error(Error::PresetImport))
+                    }
+                }
+            }
+            Msg::ExportPresets => {
+                match Preset::export(&self.data.presets()) {
+                    Ok(output) => download("presets.json", &output),
+                    Err(err) => log::error!("{}", err),
+                }
+                false
+            }
+            Msg::KeepLinkSettings => {
+                self.own_settings = None;
+                self.store_data(ctx);
+                true
+            }
+            Msg::UpdateUntilLongBreak(value) => {
+                let cycles = self.validate(Setting::UntilLongBreak, cycles(&value));
                 self.until_long_break_buffer = value;
-                self.pomo.cycles_until_long_break =
-                    self.total_cycles_buffer.parse::<usize>().unwrap_or(8);
+                if let Some(cycles) = cycles {
+                    self.pomo.pomo_mut().cycles_until_long_break = cycles;
+                    self.data.settings.cycles_until_long_break = cycles;
+                    self.store_data(ctx);
+                }
                 true
             }
             Msg::UpdateTotalCycles(value) => {
-                if LocalStorage::set(TOTAL_CYCLES_KEY, value.clone()).is_err() {
-                    self.update(ctx, Msg::Error(Error::LocalStorageWrite));
-                }
+                let cycles = self.validate(Setting::TotalCycles, cycles(&value));
                 self.total_cycles_buffer = value;
-                self.pomo.total_cycles = self.total_cycles_buffer.parse::<usize>().unwrap_or(8);
+                if let Some(cycles) = cycles {
+                    self.pomo.pomo_mut().total_cycles = cycles;
+                    self.data.settings.total_cycles = cycles;
+                    self.store_data(ctx);
+                }
+                true
+            }
+            Msg::Error(err) => {
+                log::error!("{}", err);
+                let id = self.next_toast;
+                self.next_toast += 1;
+                self.toasts.push(Toast::new(
+                    id,
+                    err.to_string(),
+                    ctx.link().callback(Msg::DismissToast),
+                ));
                 true
             }
-            Msg::Error(msg) => {
-                log::error!("{}", msg);
+            Msg::DismissToast(id) => {
+                self.toasts.retain(|toast| toast.id != id);
                 true
             }
-            Msg::PomoMessage(message) => {
-                if let PomoMessage::Transition(_) = message {
-                    self.store_tasks(ctx);
+            Msg::StoreTasks => {
+                self.store_data(ctx);
+                false
+            }
+            Msg::Record(entry) => {
+                self.data.history.push(entry);
+                self.store_data(ctx);
+                false
+            }
+            Msg::ImportTasks(file) => {
+                if let Some(file) = file {
+                    let format = TaskFormat::from_file_name(&file.name());
+                    let link = ctx.link().clone();
+                    self.reader = Some(gloo::file::callbacks::read_as_text(
+                        &file.into(),
+                        move |result| match result {
+                            Ok(input) => link.send_message(Msg::TasksRead(format, input)),
+                            Err(_) => link.send_message(Msg::Error(Error::FileRead)),
+                        },
+                    ));
+                }
+                false
+            }
+            Msg::TasksRead(format, input) => {
+                self.reader = None;
+                for task in format.parse(&input) {
+                    // completed tasks are only kept in the store
+                    if task.is_completed() {
+                        self.data.tasks.push(task);
+                    } else {
+                        self.command(ctx, PomoCommand::AddTask(task));
+                    }
+                }
+                self.store_data(ctx);
+                true
+            }
+            Msg::UpdateSessionUrl(value) => {
+                self.session_url_buffer = value;
+                true
+            }
+            Msg::Join => {
+                self.session = None;
+                match Session::join(
+                    &self.session_url_buffer,
+                    ctx.link().callback(Msg::Snapshot),
+                    ctx.link().callback(|_| Msg::Leave),
+                ) {
+                    Ok(session) => {
+                        self.session = Some(session);
+                        true
+                    }
+                    Err(err) => self.update(ctx, Msg::Error(err)),
+                }
+            }
+            Msg::Leave => {
+                if self.session.take().is_some() {
+                    // continue with the local tasks
+                    self.pomo.execute(PomoCommand::Reset);
+                    self.pomo.pomo_mut().tasks = self.data.pending_tasks();
+                }
+                true
+            }
+            Msg::Snapshot(snapshot) => {
+                let message = self.pomo.pomo_mut().apply_snapshot(snapshot);
+                self.pomo.notify(&message);
+                true
+            }
+            Msg::PhaseEnded(state) => {
+                if self.preferences.notifications {
+                    if let Err(err) = Alarm::notify(state) {
+                        self.update(ctx, Msg::Error(err));
+                    }
+                }
+                if let Some(sound) = self.preferences.alarm {
+                    if let Err(err) = self.alarm.play(sound) {
+                        self.update(ctx, Msg::Error(err));
+                    }
+                }
+                false
+            }
+            Msg::ToggleNotifications => {
+                if self.preferences.notifications {
+                    self.update(ctx, Msg::NotificationsAllowed(false))
+                } else {
+                    ctx.link().send_future(async {
+                        Msg::NotificationsAllowed(Alarm::request_permission().await)
+                    });
+                    false
+                }
+            }
+            Msg::NotificationsAllowed(allowed) => {
+                self.preferences.notifications = allowed;
+                self.store_preferences(ctx);
+                true
+            }
+            Msg::SelectAlarm(sound) => {
+                self.preferences.alarm = sound;
+                self.store_preferences(ctx);
+                self.update(ctx, Msg::TestAlarm)
+            }
+            Msg::TestAlarm => {
+                if let Some(sound) = self.preferences.alarm {
+                    if let Err(err) = self.alarm.play(sound) {
+                        self.update(ctx, Msg::Error(err));
+                    }
                 }
-
                 true
             }
+            Msg::ExportTasks(format) => {
+                self.store_data(ctx);
+                let output = format.format(&self.data.tasks);
+                download(format.file_name(), &output);
+                false
+            }
             Msg::SetTab(tab) => {
                 self.state = tab;
+                if let Some(history) = ctx.link().history() {
+                    history.push(tab.route());
+                }
                 true
             }
             Msg::SkipTo(state) => {
-                self.pomo.skip_to(state);
+                self.command(ctx, PomoCommand::SkipTo(state));
+                true
+            }
+            Msg::Shortcut(action) => {
+                let paused = self.pomo.is_paused();
+                let started = self.pomo.state() != PomoState::NotStarted;
+                match action {
+                    Action::StartStop if paused => false,
+                    Action::StartStop if started => self.update(ctx, Msg::Stop),
+                    Action::StartStop => self.update(ctx, Msg::Start),
+                    Action::PauseResume if !started => false,
+                    Action::PauseResume if paused => self.update(ctx, Msg::Resume),
+                    Action::PauseResume => self.update(ctx, Msg::Pause),
+                    Action::SkipToWorking | Action::SkipToBreak | Action::SkipToLongBreak
+                        if !started =>
+                    {
+                        false
+                    }
+                    Action::SkipToWorking => self.update(ctx, Msg::SkipTo(PomoState::Working)),
+                    Action::SkipToBreak => self.update(ctx, Msg::SkipTo(PomoState::Break)),
+                    Action::SkipToLongBreak => self.update(ctx, Msg::SkipTo(PomoState::LongBreak)),
+                    Action::NewTask => {
+                        self.focus = Some(self.task_input.clone());
+                        self.update(ctx, Msg::SetTab(TabState::Tasks))
+                    }
+                    Action::Help => {
+                        self.show_help = !self.show_help;
+                        true
+                    }
+                }
+            }
+            Msg::UpdateShortcut(action, key) => {
+                Shortcuts::set(&mut self.preferences.shortcuts, action, &key);
+                self._shortcuts = self.shortcuts().listen(ctx.link().callback(Msg::Shortcut));
+                self.store_preferences(ctx);
+                true
+            }
+            Msg::CloseHelp => {
+                self.show_help = false;
+                true
+            }
+            Msg::SelectTheme(theme) => {
+                self.preferences.theme = theme;
+                self.themes.apply(theme);
+                self.store_preferences(ctx);
+                true
+            }
+            Msg::UpdateAccent(state, color) => {
+                Accents::set(&mut self.preferences.accents, state, &color);
+                self.store_preferences(ctx);
+                true
+            }
+            Msg::ResetAccents => {
+                self.preferences.accents.clear();
+                self.store_preferences(ctx);
                 true
             }
             Msg::Tick => {
-                let message = self.pomo.update();
+                // a shared session decides when to transition
+                if self.session.is_none()
+                    && self.pomo.execute(PomoCommand::Update) != PomoMessage::NoMessage
+                {
+                    self.schedule_deadline();
+                }
                 if let Some(timer) = self.pomo.timer() {
                     self.progress = format!(
                         "{}",
@@ -237,11 +840,13 @@ impl Component for App {
                     );
                     self.goal = format!("{}", timer.goal().as_secs());
                 }
-                self.update(ctx, Msg::PomoMessage(message))
+                gloo::utils::document().set_title(&self.title());
+                true
             }
         }
     }
 
+    #[allow(clippy::unnecessary_operation)]
     fn view(&self, ctx: &Context<Self>) -> Html {
         html! {
             <section class="section">
@@ -251,6 +856,7 @@ impl Component for App {
                             match self.state {
                                 TabState::Settings => self.view_settings(ctx),
                                 TabState::Tasks => self.view_task_list(ctx),
+                                TabState::Stats => view_stats(&self.data.history),
                                 _ => self.view_timer(ctx)
                             }
                         }
@@ -265,6 +871,11 @@ impl Component for App {
                                 <Icon class="fas fa-list-alt" alt={"Tasks"}></Icon>
                                 <p>{"Tasks"}</p>
                             </a>
+                            <a class={classes!(BottomBar::item_class(), self.get_tab_active(TabState::Stats))}
+                               onclick={ctx.link().callback(|_| Msg::SetTab(TabState::Stats))}>
+                                <Icon class="fas fa-chart-bar" alt={"Stats"}></Icon>
+                                <p>{"Stats"}</p>
+                            </a>
                             <a class={classes!(BottomBar::item_class(),
                                 self.get_tab_active(TabState::Settings))}
                                onclick={ctx.link().callback(|_| Msg::SetTab(TabState::Settings))}>
@@ -272,14 +883,139 @@ impl Component for App {
                                 <p>{"Settings"}</p>
                             </a>
                         </BottomBar>
+                        { view_help(&self.shortcuts(), self.show_help, ctx.link().callback(|_| Msg::CloseHelp)) }
+                        { view_toasts(&self.toasts, ctx.link().callback(Msg::DismissToast)) }
                     </div>
                 </div>
             </section>
         }
     }
+
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        if let Some(input) = self
+            .focus
+            .take()
+            .and_then(|focus| focus.cast::<web_sys::HtmlInputElement>())
+        {
+            let _ = input.focus();
+        }
+    }
 }
 
 impl App {
+    /// Settings and sessions can be shared as query parameters, e.g.
+    /// /timer?preset=classic&work=50m&break=10m&autostart=1 or ?join=<url>
+    fn apply_query(&mut self, ctx: &Context<Self>) {
+        let search = gloo::utils::window()
+            .location()
+            .search()
+            .unwrap_or_default();
+        if search.is_empty() {
+            return;
+        }
+        let params = match web_sys::UrlSearchParams::new_with_str(&search) {
+            Ok(params) => params,
+            Err(_) => return,
+        };
+
+        // a restored session keeps running as it is
+        if !self.is_timer_running() {
+            // a shared link must not replace the visitor's own settings
+            let names = [
+                "preset",
+                "work",
+                "break",
+                "long-break",
+                "until-long-break",
+                "cycles",
+            ];
+            if names.iter().any(|name| params.has(name)) {
+                self.own_settings = Some(self.data.settings.clone());
+            }
+
+            // the preset comes first so the other parameters can override it
+            if let Some(name) = params.get("preset") {
+                match self.data.presets().into_iter().find(|p| p.name == name) {
+                    Some(preset) => {
+                        self.update(ctx, Msg::ApplyPreset(preset));
+                    }
+                    None => log::error!("Unknown preset '{}'", name),
+                }
+            }
+
+            let settings: [(&str, SettingMsg); 5] = [
+                ("work", Msg::UpdateWorkTime),
+                ("break", Msg::UpdateShortBreakTime),
+                ("long-break", Msg::UpdateLongBreakTime),
+                ("until-long-break", Msg::UpdateUntilLongBreak),
+                ("cycles", Msg::UpdateTotalCycles),
+            ];
+            for (name, msg) in settings {
+                if let Some(value) = params.get(name) {
+                    self.update(ctx, msg(value));
+                }
+            }
+        }
+
+        if let Some(url) = params.get("join") {
+            self.session_url_buffer = url;
+            self.update(ctx, Msg::Join);
+        }
+
+        let autostart = params.get("autostart");
+        if matches!(autostart.as_deref(), Some("1") | Some("true"))
+            && self.pomo.state() == PomoState::NotStarted
+        {
+            self.update(ctx, Msg::Start);
+        }
+    }
+
+    /// a link that opens the timer with the current settings
+    fn share_url(&self) -> String {
+        let settings = &self.data.settings;
+        let params = [
+            ("work", TimeParser::format(settings.work_time)),
+            ("break", TimeParser::format(settings.short_break_time)),
+            ("long-break", TimeParser::format(settings.long_break_time)),
+            (
+                "until-long-break",
+                settings.cycles_until_long_break.to_string(),
+            ),
+            ("cycles", settings.total_cycles.to_string()),
+        ];
+        let query = web_sys::UrlSearchParams::new()
+            .map(|query| {
+                for (name, value) in params {
+                    query.append(name, &value);
+                }
+                String::from(query.to_string())
+            })
+            .unwrap_or_default();
+        let origin = gloo::utils::window()
+            .location()
+            .origin()
+            .unwrap_or_default();
+        format!("{}{}?{}", origin, AppRoute::Timer.to_path(), query)
+    }
+
+    fn shortcuts(&self) -> Shortcuts {
+        Shortcuts::new(&self.preferences.shortcuts)
+    }
+
+    /// remembers the error of a setting so it is shown next to its input
+    fn validate<T>(&mut self, setting: Setting, value: Result<T, String>) -> Option<T> {
+        match value {
+            Ok(value) => {
+                self.setting_errors.remove(&setting);
+                Some(value)
+            }
+            Err(err) => {
+                self.setting_errors.insert(setting, err);
+                None
+            }
+        }
+    }
+
     fn get_tab_active(&self, state: TabState) -> String {
         if state == self.state {
             "is-active".into()
@@ -288,30 +1024,101 @@ impl App {
         }
     }
 
-    fn store_tasks(&mut self, ctx: &Context<Self>) {
-        // collect task strings and push to local storage
-        let tasks = self
-            .pomo
-            .tasks
-            .iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<_>>();
-        if LocalStorage::set(TASKS_KEY, tasks).is_err() {
+    /// the index of a task that was at index before,
+    /// None if the task is gone
+    fn find_task(&self, index: usize, description: &str) -> Option<usize> {
+        let tasks = self.pomo.tasks();
+        match tasks.get(index) {
+            Some(task) if task.description() == description => Some(index),
+            _ => tasks
+                .iter()
+                .position(|task| task.description() == description),
+        }
+    }
+
+    /// sends a command to the shared session
+    /// or executes it locally
+    fn command(&mut self, ctx: &Context<Self>, command: PomoCommand<SimpleTask>) {
+        match &self.session {
+            Some(session) => {
+                if let Err(err) = session.send(command) {
+                    self.update(ctx, Msg::Error(err));
+                }
+            }
+            None => {
+                self.pomo.execute(command);
+                self.store_data(ctx);
+                self.schedule_deadline();
+            }
+        }
+    }
+
+    /// the end of the running phase is ticked on time
+    /// instead of with the next regular tick
+    fn schedule_deadline(&mut self) {
+        let deadline = match (&self.session, self.pomo.next_update()) {
+            // longer timeouts overflow and fire right away
+            (None, Some(next)) => Some(next.as_millis().min(i32::MAX as u128) as u32),
+            _ => None,
+        };
+        self.ticker.send(TickerInput::Deadline(deadline));
+    }
+
+    fn store_data(&mut self, ctx: &Context<Self>) {
+        // a shared session has its own tasks
+        if self.session.is_none() {
+            self.data.set_pending_tasks(self.pomo.tasks());
+            self.data.session = match self.pomo.state() {
+                PomoState::NotStarted | PomoState::Completed => None,
+                _ => Some(SavedSession::new(self.pomo.pomo().snapshot(), Utc::now())),
+            };
+        }
+        let saved = match &self.own_settings {
+            Some(settings) => self.store.save(&StoreData {
+                settings: settings.clone(),
+                ..self.data.clone()
+            }),
+            None => self.store.save(&self.data),
+        };
+        if saved.is_err() {
             self.update(ctx, Msg::Error(Error::LocalStorageWrite));
         }
     }
 
+    fn store_preferences(&mut self, ctx: &Context<Self>) {
+        if let Err(err) = self.preferences.save() {
+            self.update(ctx, Msg::Error(err));
+        }
+    }
+
+    /// the countdown is shown in the title for background tabs
+    fn title(&self) -> String {
+        let remaining = self.pomo.timer().and_then(|timer| timer.remaining());
+        match (self.pomo.state(), remaining) {
+            (PomoState::Paused, _) => "Paused - PomodoDragon".into(),
+            (state, Some(remaining)) => format!(
+                "{} {} - PomodoDragon",
+                TimeParser::format_clock(remaining),
+                state
+            ),
+            _ => "PomodoDragon".into(),
+        }
+    }
+
     fn is_timer_running(&self) -> bool {
         if let Some(timer) = self.pomo.timer() {
-            timer.elapsed() != None
+            timer.elapsed().is_some()
         } else {
             self.pomo.is_paused()
         }
     }
 
     fn view_timer(&self, ctx: &Context<Self>) -> Html {
+        let accent = Accents::color(&self.preferences.accents, self.pomo.state());
         html! {
-            <div class="container box is-primary has-text-centered">
+            <div class={classes!("container", "box", "is-primary", "has-text-centered",
+                    accent.is_some().then_some("phase-box"))}
+                style={accent.as_ref().map(|color| format!("--phase-color: {}", color))}>
                 <div class="">
                     <div class="title">
                         { self.pomo.state() }
@@ -439,80 +1246,182 @@ impl App {
         }
     }
 
+    fn setting_input(&self, value: &str, setting: Setting) -> SettingInput {
+        SettingInput {
+            value: value.into(),
+            error: self.setting_errors.get(&setting).cloned(),
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
     fn view_settings(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let preferences = &self.preferences;
         html! {
             <div class="content box">
-                <article class="content">
-                    <label>
-                        <Input
-                            input_class="input is-primary"
-                            value={self.work_time_buffer.clone()}
-                            oninput={ctx.link().callback(
-                                Msg::UpdateWorkTime)}
-                            min={1}
-                            disabled={self.is_timer_running()}
-                            label="Work"
-                            kind={InputKind::Number}
-                        />
-                    </label>
-                    <label>
-                        <Input
-                            input_class="input is-primary"
-                            value={self.short_break_time_buffer.clone()}
-                            oninput={ctx.link().callback(
-                                Msg::UpdateShortBreakTime)}
-                            min={1}
-                            disabled={self.is_timer_running()}
-                            label="Short Break"
-                            kind={InputKind::Number}
-                        />
-                    </label>
+                {
+                    if self.own_settings.is_some() {
+                        html! {
+                            <div class="notification is-info">
+                                { "These settings come from a link and are not saved. " }
+                                <button class="button is-small"
+                                    onclick={link.callback(|_| Msg::KeepLinkSettings)}>
+                                    { "Keep Them" }
+                                </button>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                <PresetSettings
+                    presets={self.data.presets()}
+                    saved={self.data.presets.iter().map(|preset| preset.name.clone()).collect::<Vec<_>>()}
+                    settings={self.data.settings.clone()}
+                    disabled={self.is_timer_running()}
+                    name={self.preset_name_buffer.clone()}
+                    onapply={link.callback(Msg::ApplyPreset)}
+                    ondelete={link.callback(Msg::DeletePreset)}
+                    onname={link.callback(Msg::UpdatePresetName)}
+                    onsave={link.callback(|_| Msg::SavePreset)}
+                    onimport={link.callback(Msg::ImportPresets)}
+                    onexport={link.callback(|_| Msg::ExportPresets)}
+                />
+                <TimerSettings
+                    work_time={self.setting_input(&self.work_time_buffer, Setting::WorkTime)}
+                    short_break_time={self.setting_input(&self.short_break_time_buffer, Setting::ShortBreakTime)}
+                    long_break_time={self.setting_input(&self.long_break_time_buffer, Setting::LongBreakTime)}
+                    until_long_break={self.setting_input(&self.until_long_break_buffer, Setting::UntilLongBreak)}
+                    total_cycles={self.setting_input(&self.total_cycles_buffer, Setting::TotalCycles)}
+                    disabled={self.is_timer_running()}
+                    share_url={self.share_url()}
+                    onworktime={link.callback(Msg::UpdateWorkTime)}
+                    onshortbreaktime={link.callback(Msg::UpdateShortBreakTime)}
+                    onlongbreaktime={link.callback(Msg::UpdateLongBreakTime)}
+                    onuntillongbreak={link.callback(Msg::UpdateUntilLongBreak)}
+                    ontotalcycles={link.callback(Msg::UpdateTotalCycles)}
+                    onnormalize={link.callback(|_| Msg::NormalizeSettings)}
+                />
+                <AlarmSettings
+                    notifications={preferences.notifications}
+                    alarm={preferences.alarm}
+                    onnotifications={link.callback(|_| Msg::ToggleNotifications)}
+                    onalarm={link.callback(Msg::SelectAlarm)}
+                    ontest={link.callback(|_| Msg::TestAlarm)}
+                />
+                <ThemeSettings
+                    theme={preferences.theme}
+                    accents={preferences.accents.clone()}
+                    ontheme={link.callback(Msg::SelectTheme)}
+                    onaccent={link.callback(|(state, color)| Msg::UpdateAccent(state, color))}
+                    onreset={link.callback(|_| Msg::ResetAccents)}
+                />
+                <ShortcutSettings
+                    shortcuts={self.shortcuts()}
+                    onshortcut={link.callback(|(action, key)| Msg::UpdateShortcut(action, key))}
+                />
+                { self.view_session(ctx) }
+            </div>
+        }
+    }
 
-                    <label>
-                        <Input
-                            input_class="input is-primary"
-                            value={self.long_break_time_buffer.clone()}
-                            oninput={ctx.link().callback(
-                                Msg::UpdateLongBreakTime)}
-                            min={1}
-                            disabled={self.is_timer_running()}
-                            label="Long Break"
-                            kind={InputKind::Number}
-                        />
-                    </label>
+    #[allow(clippy::unnecessary_operation)]
+    fn view_session(&self, ctx: &Context<Self>) -> Html {
+        let button = match &self.session {
+            Some(_) => html! {
+                <button class="button is-danger"
+                    onclick={ctx.link().callback(|_| Msg::Leave)}>
+                    { "Leave" }
+                </button>
+            },
+            None => html! {
+                <button class="button is-primary"
+                    onclick={ctx.link().callback(|_| Msg::Join)}>
+                    { "Join" }
+                </button>
+            },
+        };
 
-                    <label>
-                        <Input
-                            input_class="input is-primary"
-                            value={self.until_long_break_buffer.clone()}
-                            oninput={ctx.link().callback(
-                                Msg::UpdateUntilLongBreak)}
-                            min={1}
-                            label="Cycles Until Long Break"
-                            kind={InputKind::Number}
-                        />
-                    </label>
-                    <label>
-                        <Input
-                            input_class="input is-primary"
-                            value={self.total_cycles_buffer.clone()}
-                            oninput={ctx.link().callback(
-                                Msg::UpdateTotalCycles)}
-                            min={1}
-                            label="Total Cycles"
-                            kind={InputKind::Number}
-                        />
-                    </label>
-                </article>
-            </div>
+        html! {
+            <article class="content">
+                <label>
+                    <Input
+                        input_class="input is-primary"
+                        value={self.session.as_ref()
+                            .map(|session| session.url().to_string())
+                            .unwrap_or_else(|| self.session_url_buffer.clone())}
+                        oninput={ctx.link().callback(Msg::UpdateSessionUrl)}
+                        placeholder="ws://127.0.0.1:9090"
+                        disabled={self.session.is_some()}
+                        label="Shared Session"
+                        kind={InputKind::Text}
+                    />
+                </label>
+                { button }
+            </article>
         }
     }
 
+    #[allow(clippy::unnecessary_operation)]
     fn view_task(&self, task: &SimpleTask, index: usize, ctx: &Context<Self>) -> Html {
+        // descriptions may repeat, so the current task is compared by address
+        let current = self
+            .pomo
+            .task()
+            .is_some_and(|current| std::ptr::eq(current, task));
+        let description = match &self.editing {
+            Some(editing) if editing.index == index => html! {
+                <Input
+                    class="is-flex-grow-1 mx-2"
+                    input_class="input is-small"
+                    kind={InputKind::Text}
+                    node_ref={self.edit_input.clone()}
+                    value={editing.description.clone()}
+                    oninput={ctx.link().callback(Msg::UpdateEdit)}
+                    onchange={ctx.link().callback(|_| Msg::SaveEdit)}
+                    onkeypress={ctx.link().batch_callback(|e: KeyboardEvent| {
+                        if e.key() == "Enter" { Some(Msg::SaveEdit) } else { None }
+                    })}
+                />
+            },
+            _ => html! {
+                <span class="is-flex-grow-1 mx-2"
+                    title="Double click to edit"
+                    ondblclick={ctx.link().callback(move |_| Msg::Edit(index))}>
+                    { task.to_string() }
+                </span>
+            },
+        };
+
         html! {
-            <div class="message">
+            <div class={classes!("message", if current { "is-primary" } else { "is-dark" })}
+                draggable="true"
+                ondragstart={ctx.link().callback(move |e: DragEvent| {
+                    // firefox only drags with data
+                    if let Some(data) = e.data_transfer() {
+                        let _ = data.set_data("text/plain", &index.to_string());
+                    }
+                    Msg::DragStart(index)
+                })}
+                ondragover={Callback::from(|e: DragEvent| e.prevent_default())}
+                ondrop={ctx.link().callback(move |e: DragEvent| {
+                    e.prevent_default();
+                    Msg::Drop(index)
+                })}>
                 <div class="message-header">
-                    { task.to_string() }
+                    <input
+                        type="checkbox"
+                        title="Done"
+                        onclick={ctx.link().callback(move |_| Msg::Complete(index))}
+                    />
+                    { description }
+                    {
+                        if current {
+                            html! { <span class="tag is-light mr-2">{ "Current" }</span> }
+                        } else {
+                            html! {}
+                        }
+                    }
                     <button
                         class="delete"
                         aria-label="delete"
@@ -523,6 +1432,59 @@ impl App {
         }
     }
 
+    fn view_completed_task(&self, task: &SimpleTask, index: usize, ctx: &Context<Self>) -> Html {
+        html! {
+            <div class="message is-light">
+                <div class="message-header">
+                    <input
+                        type="checkbox"
+                        title="Reopen"
+                        checked=true
+                        onclick={ctx.link().callback(move |_| Msg::Reopen(index))}
+                    />
+                    <del class="is-flex-grow-1 mx-2">{ task.to_string() }</del>
+                </div>
+            </div>
+        }
+    }
+
+    fn view_task_files(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div class="buttons">
+                <div class="file is-info">
+                    <label class="file-label">
+                        <input
+                            class="file-input"
+                            type="file"
+                            accept=".txt,.md,.markdown"
+                            onchange={ctx.link().callback(|e: Event| {
+                                let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                                Msg::ImportTasks(input.files().and_then(|files| files.get(0)))
+                            })}
+                        />
+                        <span class="file-cta">
+                            <Icon class="fas fa-upload" alt={"Import"} />
+                            <span class="file-label">{ "Import" }</span>
+                        </span>
+                    </label>
+                </div>
+                <button
+                    class="button is-info"
+                    onclick={ctx.link().callback(|_| Msg::ExportTasks(TaskFormat::TodoTxt))}>
+                    <Icon class="fas fa-download" alt={"Export"} />
+                    <span>{ "todo.txt" }</span>
+                </button>
+                <button
+                    class="button is-info"
+                    onclick={ctx.link().callback(|_| Msg::ExportTasks(TaskFormat::Markdown))}>
+                    <Icon class="fas fa-download" alt={"Export"} />
+                    <span>{ "Markdown" }</span>
+                </button>
+            </div>
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
     fn view_task_list(&self, ctx: &Context<Self>) -> Html {
         html! {
             <div class="container box">
@@ -533,6 +1495,7 @@ impl App {
                          input_class="input is-primary"
                          kind={InputKind::Text}
                          placeholder="What needs to be done?"
+                         node_ref={self.task_input.clone()}
                          value={self.description_buffer.clone()}
                          oninput={ctx.link().callback(
                              Msg::Update)
@@ -550,11 +1513,18 @@ impl App {
                         </div>
                     </div>
                 </article>
+                { self.view_task_files(ctx) }
                 {
                     for self.pomo.tasks().iter()
                         .enumerate()
                         .map(|(i, task)| self.view_task(task, i,ctx))
                 }
+                {
+                    for self.data.tasks.iter()
+                        .enumerate()
+                        .filter(|(_, task)| task.is_completed())
+                        .map(|(i, task)| self.view_completed_task(task, i, ctx))
+                }
             </div>
         }
     }
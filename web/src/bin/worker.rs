@@ -0,0 +1,9 @@
+#[path = "../ticker.rs"]
+mod ticker;
+
+use ticker::Ticker;
+use yew_agent::Threaded;
+
+fn main() {
+    Ticker::register();
+}
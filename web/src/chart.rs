@@ -0,0 +1,57 @@
+use yew::{html, Html};
+
+const WIDTH: f64 = 320.0;
+const HEIGHT: f64 = 160.0;
+/// room for the labels below the bars
+const LABEL_HEIGHT: f64 = 20.0;
+
+/// A single bar of a chart
+pub struct Bar {
+    pub label: String,
+    pub value: f64,
+    /// shown when the bar is hovered
+    pub title: String,
+}
+
+/// Renders bars as an svg that scales to its container
+pub fn bar_chart(bars: &[Bar]) -> Html {
+    let max = bars.iter().map(|bar| bar.value).fold(0.0, f64::max);
+    let slot = WIDTH / bars.len().max(1) as f64;
+    let chart_height = HEIGHT - LABEL_HEIGHT;
+
+    let bars = bars.iter().enumerate().map(|(i, bar)| {
+        let height = if max > 0.0 {
+            bar.value / max * chart_height
+        } else {
+            0.0
+        };
+        let x = i as f64 * slot;
+        html! {
+            <g>
+                <title>{ bar.title.clone() }</title>
+                <rect
+                    class="has-text-primary"
+                    fill="currentColor"
+                    x={(x + slot * 0.15).to_string()}
+                    y={(chart_height - height).to_string()}
+                    width={(slot * 0.7).to_string()}
+                    height={height.to_string()}
+                    rx="2" />
+                <text
+                    fill="currentColor"
+                    x={(x + slot / 2.0).to_string()}
+                    y={(HEIGHT - 5.0).to_string()}
+                    text-anchor="middle"
+                    font-size="10">
+                    { bar.label.clone() }
+                </text>
+            </g>
+        }
+    });
+
+    html! {
+        <svg viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} width="100%">
+            { for bars }
+        </svg>
+    }
+}
@@ -3,6 +3,13 @@ use std::fmt::Display;
 
 pub enum Error {
     LocalStorageWrite,
+    FileRead,
+    SessionJoin,
+    SessionSend,
+    Audio,
+    Notification,
+    InvalidSettings,
+    PresetImport,
 }
 
 impl Display for Error {
@@ -12,6 +19,13 @@ impl Display for Error {
             "{}",
             match self {
                 Self::LocalStorageWrite => "Local Storage Write Failed",
+                Self::FileRead => "File Read Failed",
+                Self::SessionJoin => "Unable To Join Session",
+                Self::SessionSend => "Unable To Reach Session",
+                Self::Audio => "Unable To Play Alarm",
+                Self::Notification => "Notifications Are Not Allowed",
+                Self::InvalidSettings => "Please Fix The Settings First",
+                Self::PresetImport => "Invalid Preset File",
             }
         )
     }
@@ -0,0 +1,39 @@
+use crate::shortcuts::{Action, Shortcuts};
+use yew::{classes, html, Callback, Html};
+
+/// Renders the keyboard shortcuts as a modal,
+/// disabled shortcuts are left out
+pub fn view_help(shortcuts: &Shortcuts, active: bool, close: Callback<()>) -> Html {
+    let rows = Action::ALL
+        .into_iter()
+        .filter(|action| !shortcuts.key(*action).is_empty())
+        .map(|action| {
+            html! {
+                <tr>
+                    <td><kbd>{ Shortcuts::display_key(shortcuts.key(action)) }</kbd></td>
+                    <td>{ action.description() }</td>
+                </tr>
+            }
+        });
+
+    html! {
+        <div class={classes!("modal", active.then_some("is-active"))}>
+            <div class="modal-background" onclick={close.reform(|_| ())}></div>
+            <div class="modal-content">
+                <div class="box content">
+                    <h4>{ "Keyboard Shortcuts" }</h4>
+                    <table class="table is-fullwidth">
+                        <tbody>
+                            { for rows }
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+            <button
+                class="modal-close is-large"
+                aria-label="close"
+                onclick={close.reform(|_| ())}>
+            </button>
+        </div>
+    }
+}
@@ -2,12 +2,16 @@ use web_sys::HtmlInputElement as InputElement;
 use yew::prelude::*;
 use yew::Properties;
 
-pub enum Msg {}
+pub enum Msg {
+    Input(String),
+    Change(String),
+}
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum InputKind {
     Text,
     Number,
+    Color,
 }
 
 #[derive(Properties, PartialEq, Clone)]
@@ -28,7 +32,7 @@ pub struct InputProps {
 
     #[prop_or(0)]
     pub min: i64,
-    #[prop_or(std::i64::MAX)]
+    #[prop_or(i64::MAX)]
     pub max: i64,
 
     #[prop_or_default]
@@ -37,59 +41,125 @@ pub struct InputProps {
     #[prop_or(InputKind::Text)]
     pub kind: InputKind,
 
+    /// only receives values that passed the validation of the kind
     #[prop_or_default]
     pub oninput: Callback<String>,
 
+    /// the user is done editing, e.g. the input lost focus
+    #[prop_or_default]
+    pub onchange: Callback<String>,
+
     #[prop_or_default]
     pub onkeypress: Callback<KeyboardEvent>,
+
+    /// refers to the input element, e.g. to focus it
+    #[prop_or_default]
+    pub node_ref: NodeRef,
+
+    /// an error found by the owner, e.g. a value that can not be parsed
+    #[prop_or_default]
+    pub error: Option<String>,
 }
 
-pub struct Input {}
+pub struct Input {
+    /// the last value was rejected by the validation
+    error: Option<String>,
+}
 
 impl Component for Input {
     type Message = Msg;
     type Properties = InputProps;
 
     fn create(_ctx: &Context<Self>) -> Self {
-        Self {}
+        Self { error: None }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, _msg: Self::Message) -> bool {
-        false
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Input(value) => {
+                self.error = Self::validate(ctx.props(), &value).err();
+                if self.error.is_none() {
+                    ctx.props().oninput.emit(value);
+                }
+            }
+            Msg::Change(value) => {
+                if self.error.is_none() {
+                    ctx.props().onchange.emit(value);
+                }
+            }
+        }
+        true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        let callback = ctx.props().oninput.clone();
+        let error = self.error.as_ref().or(ctx.props().error.as_ref());
         html! {
             <div class={ctx.props().class.clone()}>
                 { ctx.props().label.clone() }
                 <p>
                     <input
-                        class={ctx.props().input_class.clone()}
+                        ref={ctx.props().node_ref.clone()}
+                        class={classes!(ctx.props().input_class.clone(), error.map(|_| "is-danger"))}
                         placeholder={ctx.props().placeholder.clone()}
                         value={ctx.props().value.clone()}
                         min={ctx.props().min.to_string()}
+                        max={ctx.props().max.to_string()}
                         disabled={ctx.props().disabled}
-                        oninput={move |input: InputEvent| Self::on_input(input, &callback)}
+                        oninput={ctx.link().callback(Self::on_input)}
+                        onchange={ctx.link().callback(Self::on_change)}
                         onkeypress={ctx.props().onkeypress.clone()}
                         type={Self::input_kind(ctx)} />
                 </p>
+                {
+                    if let Some(error) = error {
+                        html! { <p class="help is-danger">{ error }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
             </div>
         }
     }
 }
 
 impl Input {
-    fn on_input(e: InputEvent, callback: &Callback<String>) {
+    fn on_input(e: InputEvent) -> Msg {
         // TODO maybe don't trust this unchecked cast!
         let input: InputElement = e.target_unchecked_into();
-        callback.emit(input.value());
+        Msg::Input(input.value())
+    }
+
+    fn on_change(e: Event) -> Msg {
+        let input: InputElement = e.target_unchecked_into();
+        Msg::Change(input.value())
+    }
+
+    /// numbers have to be within min and max
+    fn validate(props: &InputProps, value: &str) -> Result<(), String> {
+        match props.kind {
+            InputKind::Number => {
+                let number = value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| "Must be a number".to_string())?;
+                if number < props.min as f64 || number > props.max as f64 {
+                    Err(match props.max {
+                        i64::MAX => format!("Must be at least {}", props.min),
+                        max => format!("Must be between {} and {}", props.min, max),
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
     }
 
     fn input_kind(ctx: &Context<Self>) -> String {
         match ctx.props().kind {
             InputKind::Text => "text",
             InputKind::Number => "number",
+            InputKind::Color => "color",
         }
         .into()
     }
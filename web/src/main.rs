@@ -1,13 +1,26 @@
 mod about;
+mod alarm;
 mod app;
 mod bottombar;
+mod chart;
 mod error;
 mod footer;
+mod help;
 mod icon;
 mod input;
 mod nav;
 mod notfound;
+mod preferences;
+mod presets;
 mod router;
+mod session;
+mod settings;
+mod shortcuts;
+mod stats;
+mod store;
+mod theme;
+mod ticker;
+mod toast;
 use app::App;
 use router::AppRouter;
 use std::panic;
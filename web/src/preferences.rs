@@ -0,0 +1,34 @@
+use crate::alarm::AlarmSound;
+use crate::error::Error;
+use crate::theme::Theme;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const PREFERENCES_KEY: &str = "pomododragon.preferences";
+
+/// Preferences of the web ui,
+/// they are stored apart from the document shared with the cli
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// show a system notification
+    pub notifications: bool,
+    /// None is silent
+    pub alarm: Option<AlarmSound>,
+    /// keys of the shortcuts that differ from the defaults by action name
+    pub shortcuts: BTreeMap<String, String>,
+    pub theme: Theme,
+    /// colors of the phases that differ from the defaults by state name
+    pub accents: BTreeMap<String, String>,
+}
+
+impl Preferences {
+    pub fn load() -> Self {
+        LocalStorage::get(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        LocalStorage::set(PREFERENCES_KEY, self).map_err(|_| Error::LocalStorageWrite)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::icon::Icon;
+use crate::input::{Input, InputKind};
+use pomododragon::{Preset, Settings};
+use yew::prelude::*;
+use yew::Properties;
+
+#[derive(Properties, PartialEq)]
+pub struct PresetSettingsProps {
+    /// the builtin presets and the ones of the user
+    pub presets: Vec<Preset>,
+    /// the names of the presets the user saved, only they can be deleted
+    pub saved: Vec<String>,
+    /// the preset with these settings is highlighted
+    pub settings: Settings,
+    /// presets can not be applied while the timer runs
+    #[prop_or_default]
+    pub disabled: bool,
+    /// the name the settings are saved as
+    #[prop_or_default]
+    pub name: String,
+
+    #[prop_or_default]
+    pub onapply: Callback<Preset>,
+    #[prop_or_default]
+    pub ondelete: Callback<String>,
+    #[prop_or_default]
+    pub onname: Callback<String>,
+    #[prop_or_default]
+    pub onsave: Callback<()>,
+    #[prop_or_default]
+    pub onimport: Callback<Option<web_sys::File>>,
+    #[prop_or_default]
+    pub onexport: Callback<()>,
+}
+
+/// Applies, saves, deletes, imports and exports presets
+pub struct PresetSettings;
+
+impl Component for PresetSettings {
+    type Message = ();
+    type Properties = PresetSettingsProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let presets = props.presets.iter().map(|preset| {
+            let active = preset.settings == props.settings;
+            let custom = props.saved.contains(&preset.name);
+            let name = preset.name.clone();
+            let apply = if props.disabled {
+                Callback::noop()
+            } else {
+                let preset = preset.clone();
+                props.onapply.reform(move |_| preset.clone())
+            };
+            html! {
+                <div class="control">
+                    <div class="tags has-addons">
+                        <a class={classes!("tag", "is-medium", active.then_some("is-primary"))}
+                            onclick={apply}>
+                            { name.clone() }
+                        </a>
+                        {
+                            if custom {
+                                html! {
+                                    <a class="tag is-medium is-delete"
+                                        onclick={props.ondelete.reform(move |_| name.clone())}>
+                                    </a>
+                                }
+                            } else {
+                                html! {}
+                            }
+                        }
+                    </div>
+                </div>
+            }
+        });
+
+        let onsave = props.onsave.clone();
+        html! {
+            <article class="content">
+                <p>{ "Presets" }</p>
+                <div class="field is-grouped is-grouped-multiline">
+                    { for presets }
+                </div>
+                <div class="field has-addons">
+                    <div class="control is-expanded">
+                        <Input
+                            input_class="input is-primary"
+                            value={props.name.clone()}
+                            oninput={props.onname.clone()}
+                            onkeypress={Callback::from(move |e: KeyboardEvent| {
+                                if e.key() == "Enter" {
+                                    onsave.emit(());
+                                }
+                            })}
+                            placeholder="Save the settings as"
+                            kind={InputKind::Text}
+                        />
+                    </div>
+                    <div class="control">
+                        <button
+                            class="button is-primary"
+                            onclick={props.onsave.reform(|_| ())}>
+                            { "Save" }
+                        </button>
+                    </div>
+                </div>
+                <div class="buttons">
+                    <div class="file is-info">
+                        <label class="file-label">
+                            <input
+                                class="file-input"
+                                type="file"
+                                accept=".json"
+                                onchange={props.onimport.reform(|e: Event| {
+                                    let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                                    input.files().and_then(|files| files.get(0))
+                                })}
+                            />
+                            <span class="file-cta">
+                                <Icon class="fas fa-upload" alt={"Import"} />
+                                <span class="file-label">{ "Import" }</span>
+                            </span>
+                        </label>
+                    </div>
+                    <button
+                        class="button is-info"
+                        onclick={props.onexport.reform(|_| ())}>
+                        <Icon class="fas fa-download" alt={"Export"} />
+                        <span>{ "Export" }</span>
+                    </button>
+                </div>
+            </article>
+        }
+    }
+}
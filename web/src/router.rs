@@ -1,4 +1,5 @@
 use crate::about::About;
+use crate::app::TabState;
 use crate::footer::Footer;
 use crate::nav::Nav;
 use crate::notfound::NotFound;
@@ -11,6 +12,18 @@ pub enum AppRoute {
     #[at("/")]
     Index,
 
+    #[at("/timer")]
+    Timer,
+
+    #[at("/tasks")]
+    Tasks,
+
+    #[at("/stats")]
+    Stats,
+
+    #[at("/settings")]
+    Settings,
+
     #[at("/about")]
     About,
 
@@ -35,6 +48,7 @@ impl Component for AppRouter {
         true
     }
 
+    #[allow(clippy::unnecessary_operation, clippy::let_unit_value)]
     fn view(&self, _ctx: &Context<Self>) -> Html {
         html! {
             <div>
@@ -44,7 +58,10 @@ impl Component for AppRouter {
                     render = {
                         Switch::render(|switch: &AppRoute| {
                             match switch {
-                                AppRoute::Index => html!{<App />},
+                                AppRoute::Index | AppRoute::Timer => html!{<App tab={TabState::Timer} />},
+                                AppRoute::Tasks => html!{<App tab={TabState::Tasks} />},
+                                AppRoute::Stats => html!{<App tab={TabState::Stats} />},
+                                AppRoute::Settings => html!{<App tab={TabState::Settings} />},
                                 AppRoute::About => html!{<About />},
                                 _ => html!{<NotFound />},
                             }
@@ -0,0 +1,69 @@
+use crate::error::Error;
+use pomododragon::{PomoCommand, PomoSnapshot, SimpleTask, WireMessage};
+use web_sys::wasm_bindgen::closure::Closure;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{Event, MessageEvent, WebSocket};
+use yew::Callback;
+
+/// A connection to a shared session hosted by pomododragon-server
+/// The connection is closed when the session is dropped
+pub struct Session {
+    socket: WebSocket,
+    url: String,
+    _onmessage: Closure<dyn FnMut(MessageEvent)>,
+    _onclose: Closure<dyn FnMut(Event)>,
+}
+
+impl Session {
+    pub fn join(
+        url: &str,
+        onsnapshot: Callback<PomoSnapshot<SimpleTask>>,
+        onclose: Callback<()>,
+    ) -> Result<Self, Error> {
+        let socket = WebSocket::new(url).map_err(|_| Error::SessionJoin)?;
+
+        let onmessage = Closure::wrap(Box::new(move |e: MessageEvent| {
+            if let Some(text) = e.data().as_string() {
+                match WireMessage::from_json(&text) {
+                    Ok(WireMessage::Snapshot(snapshot)) => onsnapshot.emit(snapshot),
+                    Ok(_) => (),
+                    Err(err) => log::error!("{}", err),
+                }
+            }
+        }) as Box<dyn FnMut(MessageEvent)>);
+        let onclose = Closure::wrap(Box::new(move |_: Event| {
+            onclose.emit(());
+        }) as Box<dyn FnMut(Event)>);
+
+        socket.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(onclose.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            url: url.into(),
+            _onmessage: onmessage,
+            _onclose: onclose,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn send(&self, command: PomoCommand<SimpleTask>) -> Result<(), Error> {
+        let command = WireMessage::Command(command)
+            .to_json()
+            .map_err(|_| Error::SessionSend)?;
+        self.socket
+            .send_with_str(&command)
+            .map_err(|_| Error::SessionSend)
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+        let _ = self.socket.close();
+    }
+}
@@ -0,0 +1,310 @@
+use crate::alarm::AlarmSound;
+use crate::icon::Icon;
+use crate::input::{Input, InputKind};
+use crate::shortcuts::{Action, Shortcuts};
+use crate::theme::{Accents, Theme};
+use pomododragon::PomoState;
+use std::collections::BTreeMap;
+use yew::prelude::*;
+use yew::Properties;
+
+/// The text of a setting's input and the error of its value
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SettingInput {
+    pub value: String,
+    pub error: Option<String>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct TimerSettingsProps {
+    pub work_time: SettingInput,
+    pub short_break_time: SettingInput,
+    pub long_break_time: SettingInput,
+    pub until_long_break: SettingInput,
+    pub total_cycles: SettingInput,
+    /// timers can not be changed while they run
+    #[prop_or_default]
+    pub disabled: bool,
+    /// a link that opens the timer with these settings
+    #[prop_or_default]
+    pub share_url: String,
+
+    #[prop_or_default]
+    pub onworktime: Callback<String>,
+    #[prop_or_default]
+    pub onshortbreaktime: Callback<String>,
+    #[prop_or_default]
+    pub onlongbreaktime: Callback<String>,
+    #[prop_or_default]
+    pub onuntillongbreak: Callback<String>,
+    #[prop_or_default]
+    pub ontotalcycles: Callback<String>,
+    /// the user is done editing a timer
+    #[prop_or_default]
+    pub onnormalize: Callback<()>,
+}
+
+/// The lengths of the phases and the number of cycles
+pub struct TimerSettings;
+
+impl Component for TimerSettings {
+    type Message = ();
+    type Properties = TimerSettingsProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let timer =
+            |label: &str, placeholder: &str, input: &SettingInput, oninput: &Callback<String>| {
+                html! {
+                    <label>
+                        <Input
+                            input_class="input is-primary"
+                            value={input.value.clone()}
+                            oninput={oninput.clone()}
+                            error={input.error.clone()}
+                            onchange={props.onnormalize.reform(|_| ())}
+                            placeholder={placeholder.to_string()}
+                            disabled={props.disabled}
+                            label={label.to_string()}
+                            kind={InputKind::Text}
+                        />
+                    </label>
+                }
+            };
+        let cycles = |label: &str, input: &SettingInput, oninput: &Callback<String>| {
+            html! {
+                <label>
+                    <Input
+                        input_class="input is-primary"
+                        value={input.value.clone()}
+                        oninput={oninput.clone()}
+                        error={input.error.clone()}
+                        min={1}
+                        label={label.to_string()}
+                        kind={InputKind::Number}
+                    />
+                </label>
+            }
+        };
+
+        html! {
+            <>
+                <article class="content">
+                    { timer("Work", "25m, 1h30m or until 14:30", &props.work_time, &props.onworktime) }
+                    { timer("Short Break", "5m, 90s or 1h", &props.short_break_time, &props.onshortbreaktime) }
+                    { timer("Long Break", "5m, 90s or 1h", &props.long_break_time, &props.onlongbreaktime) }
+                    { cycles("Cycles Until Long Break", &props.until_long_break, &props.onuntillongbreak) }
+                    { cycles("Total Cycles", &props.total_cycles, &props.ontotalcycles) }
+                </article>
+                <p>
+                    <a href={props.share_url.clone()}>{ "Link To These Settings" }</a>
+                </p>
+            </>
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AlarmSettingsProps {
+    #[prop_or_default]
+    pub notifications: bool,
+    /// None is silent
+    #[prop_or_default]
+    pub alarm: Option<AlarmSound>,
+
+    #[prop_or_default]
+    pub onnotifications: Callback<()>,
+    #[prop_or_default]
+    pub onalarm: Callback<Option<AlarmSound>>,
+    #[prop_or_default]
+    pub ontest: Callback<()>,
+}
+
+/// How the end of a phase is announced
+pub struct AlarmSettings;
+
+impl Component for AlarmSettings {
+    type Message = ();
+    type Properties = AlarmSettingsProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let selected = props.alarm;
+        let options = AlarmSound::ALL.into_iter().map(|sound| {
+            html! {
+                <option value={sound.to_string()} selected={selected == Some(sound)}>
+                    { sound.to_string() }
+                </option>
+            }
+        });
+
+        html! {
+            <article class="content">
+                <label class="checkbox">
+                    <input
+                        type="checkbox"
+                        checked={props.notifications}
+                        onclick={props.onnotifications.reform(|_| ())}
+                    />
+                    { " Notifications" }
+                </label>
+                <p>{ "Alarm" }</p>
+                <div class="field has-addons">
+                    <div class="control">
+                        <div class="select is-primary">
+                            <select onchange={props.onalarm.reform(|e: Event| {
+                                let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                                select.value().parse().ok()
+                            })}>
+                                <option value="" selected={selected.is_none()}>{ "none" }</option>
+                                { for options }
+                            </select>
+                        </div>
+                    </div>
+                    <div class="control">
+                        <button
+                            class="button is-primary"
+                            disabled={selected.is_none()}
+                            onclick={props.ontest.reform(|_| ())}>
+                            <Icon class="fas fa-volume-up" alt={"Test"} />
+                        </button>
+                    </div>
+                </div>
+            </article>
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ThemeSettingsProps {
+    #[prop_or_default]
+    pub theme: Theme,
+    /// colors of the phases that differ from the defaults
+    #[prop_or_default]
+    pub accents: BTreeMap<String, String>,
+
+    #[prop_or_default]
+    pub ontheme: Callback<Theme>,
+    #[prop_or_default]
+    pub onaccent: Callback<(PomoState, String)>,
+    #[prop_or_default]
+    pub onreset: Callback<()>,
+}
+
+/// The color scheme and the colors of the phases
+pub struct ThemeSettings;
+
+impl Component for ThemeSettings {
+    type Message = ();
+    type Properties = ThemeSettingsProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let options = Theme::ALL.into_iter().map(|theme| {
+            html! {
+                <option value={theme.to_string()} selected={props.theme == theme}>
+                    { theme.to_string() }
+                </option>
+            }
+        });
+        let accents = Accents::STATES.into_iter().map(|state| {
+            html! {
+                <div class="column">
+                    <Input
+                        input_class="input is-primary"
+                        value={Accents::color(&props.accents, state).unwrap_or_default()}
+                        oninput={props.onaccent.reform(move |color| (state, color))}
+                        label={state.to_string()}
+                        kind={InputKind::Color}
+                    />
+                </div>
+            }
+        });
+
+        let ontheme = props.ontheme.clone();
+        html! {
+            <article class="content">
+                <p>{ "Theme" }</p>
+                <div class="select is-primary">
+                    <select onchange={Callback::from(move |e: Event| {
+                        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                        if let Ok(theme) = select.value().parse() {
+                            ontheme.emit(theme);
+                        }
+                    })}>
+                        { for options }
+                    </select>
+                </div>
+                <div class="columns is-mobile">
+                    { for accents }
+                </div>
+                <button
+                    class="button is-primary"
+                    onclick={props.onreset.reform(|_| ())}>
+                    { "Reset Colors" }
+                </button>
+            </article>
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ShortcutSettingsProps {
+    pub shortcuts: Shortcuts,
+
+    #[prop_or_default]
+    pub onshortcut: Callback<(Action, String)>,
+}
+
+/// The keys of the shortcuts, an empty key disables a shortcut
+pub struct ShortcutSettings;
+
+impl Component for ShortcutSettings {
+    type Message = ();
+    type Properties = ShortcutSettingsProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let inputs = Action::ALL.into_iter().map(|action| {
+            html! {
+                <label>
+                    <Input
+                        input_class="input is-primary"
+                        value={Shortcuts::display_key(props.shortcuts.key(action))}
+                        oninput={props.onshortcut.reform(move |key| (action, key))}
+                        placeholder="Disabled"
+                        label={action.description()}
+                        kind={InputKind::Text}
+                    />
+                </label>
+            }
+        });
+
+        html! {
+            <article class="content">
+                <p>{ "Shortcuts" }</p>
+                { for inputs }
+            </article>
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use gloo::events::{EventListener, EventListenerOptions};
+use std::collections::BTreeMap;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+use yew::Callback;
+
+/// Everything that can be triggered from the keyboard
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    StartStop,
+    PauseResume,
+    SkipToWorking,
+    SkipToBreak,
+    SkipToLongBreak,
+    NewTask,
+    Help,
+}
+
+impl Action {
+    pub const ALL: [Self; 7] = [
+        Self::StartStop,
+        Self::PauseResume,
+        Self::SkipToWorking,
+        Self::SkipToBreak,
+        Self::SkipToLongBreak,
+        Self::NewTask,
+        Self::Help,
+    ];
+
+    /// the key as reported by KeyboardEvent.key
+    pub fn default_key(&self) -> &'static str {
+        match self {
+            Self::StartStop => "s",
+            Self::PauseResume => " ",
+            Self::SkipToWorking => "1",
+            Self::SkipToBreak => "2",
+            Self::SkipToLongBreak => "3",
+            Self::NewTask => "n",
+            Self::Help => "?",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::StartStop => "Start / Stop",
+            Self::PauseResume => "Pause / Resume",
+            Self::SkipToWorking => "Skip To Working",
+            Self::SkipToBreak => "Skip To Break",
+            Self::SkipToLongBreak => "Skip To Long Break",
+            Self::NewTask => "New Task",
+            Self::Help => "Show Shortcuts",
+        }
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::StartStop => "start-stop",
+                Self::PauseResume => "pause-resume",
+                Self::SkipToWorking => "skip-working",
+                Self::SkipToBreak => "skip-break",
+                Self::SkipToLongBreak => "skip-long-break",
+                Self::NewTask => "new-task",
+                Self::Help => "help",
+            }
+        )
+    }
+}
+
+/// The key of every action,
+/// an empty key disables the action
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shortcuts {
+    keys: Vec<(Action, String)>,
+}
+
+impl Shortcuts {
+    /// applies the keys that were changed in the preferences to the defaults
+    pub fn new(custom: &BTreeMap<String, String>) -> Self {
+        Self {
+            keys: Action::ALL
+                .into_iter()
+                .map(|action| {
+                    let key = custom
+                        .get(&action.to_string())
+                        .map(|key| key.as_str())
+                        .unwrap_or_else(|| action.default_key());
+                    (action, key.to_string())
+                })
+                .collect(),
+        }
+    }
+
+    /// stores a key in the preferences, the default is not stored
+    pub fn set(custom: &mut BTreeMap<String, String>, action: Action, key: &str) {
+        let key = Self::parse_key(key);
+        if key == action.default_key() {
+            custom.remove(&action.to_string());
+        } else {
+            custom.insert(action.to_string(), key);
+        }
+    }
+
+    pub fn key(&self, action: Action) -> &str {
+        self.keys
+            .iter()
+            .find(|(other, _)| *other == action)
+            .map(|(_, key)| key.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn action(&self, key: &str) -> Option<Action> {
+        self.keys
+            .iter()
+            .find(|(_, other)| !other.is_empty() && other == key)
+            .map(|(action, _)| *action)
+    }
+
+    /// the key as it is shown to the user
+    pub fn display_key(key: &str) -> String {
+        match key {
+            " " => "Space".into(),
+            _ => key.into(),
+        }
+    }
+
+    /// the inverse of display_key
+    pub fn parse_key(key: &str) -> String {
+        if key.eq_ignore_ascii_case("space") {
+            " ".into()
+        } else {
+            key.trim().into()
+        }
+    }
+
+    /// Listens for the shortcuts on the whole document.
+    /// Keys are ignored while the user is typing into a form
+    /// and when a modifier is held so browser shortcuts keep working
+    pub fn listen(self, callback: Callback<Action>) -> EventListener {
+        EventListener::new_with_options(
+            &gloo::utils::document(),
+            "keydown",
+            EventListenerOptions::enable_prevent_default(),
+            move |event| {
+                let event = match event.dyn_ref::<KeyboardEvent>() {
+                    Some(event) => event,
+                    None => return,
+                };
+                if event.ctrl_key() || event.alt_key() || event.meta_key() || Self::is_typing(event)
+                {
+                    return;
+                }
+                if let Some(action) = self.action(&event.key()) {
+                    // space would scroll and n would be typed into the focused input
+                    event.prevent_default();
+                    callback.emit(action);
+                }
+            },
+        )
+    }
+
+    fn is_typing(event: &KeyboardEvent) -> bool {
+        event
+            .target()
+            .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+            .map(|element| {
+                matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+                    || element.is_content_editable()
+            })
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::chart::{bar_chart, Bar};
+use chrono::Local;
+use pomododragon::{HistoryEntry, PeriodStats, Stats, TimeParser};
+use std::time::Duration;
+use yew::{html, Html};
+
+/// formats a duration in whole minutes, e.g. 1h30m
+fn focus_time(duration: Duration) -> String {
+    TimeParser::format(Duration::from_secs(duration.as_secs() / 60 * 60))
+}
+
+fn bars(periods: &[PeriodStats], label: &str) -> Vec<Bar> {
+    periods
+        .iter()
+        .map(|period| Bar {
+            label: period.start.format(label).to_string(),
+            value: period.focus.as_secs_f64(),
+            title: format!(
+                "{}: {}, {} pomodoros",
+                period.start,
+                focus_time(period.focus),
+                period.pomodoros
+            ),
+        })
+        .collect()
+}
+
+/// Renders the stats tab, the focus of the last days and weeks
+/// and the pomodoros of every task
+pub fn view_stats(history: &[HistoryEntry]) -> Html {
+    let today = Local::now().date_naive();
+    let stats = Stats::new(history, today, &Local, 7, 8);
+    let today_stats = stats.days.last();
+
+    html! {
+        <div class="content box">
+            <nav class="level is-mobile">
+                <div class="level-item has-text-centered">
+                    <div>
+                        <p class="heading">{ "Today" }</p>
+                        <p class="title">
+                            { focus_time(today_stats.map(|day| day.focus).unwrap_or_default()) }
+                        </p>
+                    </div>
+                </div>
+                <div class="level-item has-text-centered">
+                    <div>
+                        <p class="heading">{ "Pomodoros" }</p>
+                        <p class="title">
+                            { today_stats.map(|day| day.pomodoros).unwrap_or_default() }
+                        </p>
+                    </div>
+                </div>
+                <div class="level-item has-text-centered">
+                    <div>
+                        <p class="heading">{ "Streak" }</p>
+                        <p class="title">{ stats.streak }</p>
+                    </div>
+                </div>
+                <div class="level-item has-text-centered">
+                    <div>
+                        <p class="heading">{ "Longest Streak" }</p>
+                        <p class="title">{ stats.longest_streak }</p>
+                    </div>
+                </div>
+            </nav>
+
+            <h4>{ "Daily Focus" }</h4>
+            { bar_chart(&bars(&stats.days, "%a")) }
+
+            <h4>{ "Weekly Focus" }</h4>
+            { bar_chart(&bars(&stats.weeks, "%d.%m")) }
+
+            <h4>{ "Pomodoros Per Task" }</h4>
+            <table class="table is-fullwidth">
+                <tbody>
+                    {
+                        for stats.tasks.iter().map(|(task, pomodoros)| html! {
+                            <tr>
+                                <td>{ task }</td>
+                                <td class="has-text-right">{ pomodoros }</td>
+                            </tr>
+                        })
+                    }
+                </tbody>
+            </table>
+        </div>
+    }
+}
@@ -0,0 +1,63 @@
+use gloo::storage::{LocalStorage, Storage};
+use pomododragon::{Store, StoreError};
+use serde_json::{Map, Value};
+
+const STORE_KEY: &str = "pomododragon.store";
+
+// keys used before the store was versioned
+// and the names they have in a version 0 document
+const LEGACY_KEYS: &[(&str, &str)] = &[
+    ("pomododragon.work_timer", "work_time"),
+    ("pomododragon.break_time", "short_break_time"),
+    ("pomododragon.long_break_time", "long_break_time"),
+    ("pomododragon.total_cycles", "total_cycles"),
+    ("pomododragon.cycles_until_break", "cycles_until_long_break"),
+    ("pomododragon.tasks", "tasks"),
+];
+
+/// Stores the document under a single local storage key
+#[derive(Default)]
+pub struct LocalStorageStore;
+
+impl LocalStorageStore {
+    /// collects the legacy keys into a version 0 document
+    fn read_legacy() -> Option<String> {
+        let document = LEGACY_KEYS
+            .iter()
+            .filter_map(|(key, name)| {
+                LocalStorage::get::<Value>(key)
+                    .ok()
+                    .map(|value| (name.to_string(), value))
+            })
+            .collect::<Map<_, _>>();
+
+        if document.is_empty() {
+            None
+        } else {
+            Some(Value::Object(document).to_string())
+        }
+    }
+}
+
+impl Store for LocalStorageStore {
+    fn read(&self) -> Result<Option<String>, StoreError> {
+        let storage = LocalStorage::raw();
+        match storage.get_item(STORE_KEY) {
+            Ok(Some(document)) => Ok(Some(document)),
+            Ok(None) => Ok(Self::read_legacy()),
+            Err(_) => Err(StoreError::Read("Local storage is not available".into())),
+        }
+    }
+
+    fn write(&mut self, document: &str) -> Result<(), StoreError> {
+        LocalStorage::raw()
+            .set_item(STORE_KEY, document)
+            .map_err(|_| StoreError::Write("Local storage write failed".into()))?;
+
+        // the legacy keys are migrated now
+        for (key, _) in LEGACY_KEYS {
+            LocalStorage::delete(key);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use pomododragon::PomoState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{CssMediaRule, CssStyleSheet};
+
+/// The color scheme of the page,
+/// the system follows the browser's preference
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Self; 3] = [Self::System, Self::Light, Self::Dark];
+
+    /// The media text a stylesheet's queries are replaced with.
+    /// Only the color scheme conditions are decided by the theme,
+    /// other conditions such as breakpoints are kept
+    fn media_text(&self, text: &str) -> String {
+        if *self == Self::System {
+            return text.into();
+        }
+        text.split(',')
+            .map(|query| self.media_query(query.trim()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn media_query(&self, query: &str) -> String {
+        let (schemes, rest): (Vec<&str>, Vec<&str>) = query
+            .split(" and ")
+            .partition(|condition| condition.contains("prefers-color-scheme"));
+        if schemes.is_empty() {
+            return query.into();
+        }
+
+        // every condition has to match, so a mismatching scheme disables the whole query
+        let dark = *self == Self::Dark;
+        if schemes.iter().any(|scheme| scheme.contains("dark") != dark) {
+            "not all".into()
+        } else if rest.is_empty() {
+            "all".into()
+        } else {
+            rest.join(" and ")
+        }
+    }
+
+    fn color_scheme(&self) -> &'static str {
+        match self {
+            Self::System => "light dark",
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+}
+
+impl std::fmt::Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::System => "system",
+                Self::Light => "light",
+                Self::Dark => "dark",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.to_string() == s)
+            .ok_or_else(|| format!("Unknown theme '{}'", s))
+    }
+}
+
+/// The light and dark stylesheets are wrapped in prefers-color-scheme queries.
+/// A theme is applied by replacing those queries
+/// so the browser's preference can be restored later
+pub struct ThemeSheets {
+    rules: Vec<(CssMediaRule, String)>,
+}
+
+impl ThemeSheets {
+    /// collects the media rules with color scheme queries of the loaded stylesheets
+    pub fn load() -> Self {
+        let sheets = gloo::utils::document().style_sheets();
+        let mut rules = vec![];
+        for sheet in (0..sheets.length()).filter_map(|i| sheets.item(i)) {
+            // cross origin sheets can not be read
+            let css_rules = match sheet
+                .dyn_into::<CssStyleSheet>()
+                .ok()
+                .and_then(|sheet| sheet.css_rules().ok())
+            {
+                Some(css_rules) => css_rules,
+                None => continue,
+            };
+            for rule in (0..css_rules.length()).filter_map(|i| css_rules.item(i)) {
+                if let Ok(rule) = rule.dyn_into::<CssMediaRule>() {
+                    let query = rule.media().media_text();
+                    if query.contains("prefers-color-scheme") {
+                        rules.push((rule, query));
+                    }
+                }
+            }
+        }
+        Self { rules }
+    }
+
+    pub fn apply(&self, theme: Theme) {
+        for (rule, query) in &self.rules {
+            rule.media().set_media_text(&theme.media_text(query));
+        }
+
+        // form controls and scrollbars follow the color-scheme meta tag
+        if let Ok(Some(meta)) =
+            gloo::utils::document().query_selector("meta[name=\"color-scheme\"]")
+        {
+            let _ = meta.set_attribute("content", theme.color_scheme());
+        }
+    }
+}
+
+/// The colors of the phases, changed colors are stored
+/// in the preferences by the name of the state
+pub struct Accents;
+
+impl Accents {
+    pub const STATES: [PomoState; 4] = [
+        PomoState::Working,
+        PomoState::Break,
+        PomoState::LongBreak,
+        PomoState::Paused,
+    ];
+
+    pub fn default_color(state: PomoState) -> Option<&'static str> {
+        match state {
+            PomoState::Working => Some("#e53935"),
+            PomoState::Break => Some("#43a047"),
+            PomoState::LongBreak => Some("#1e88e5"),
+            PomoState::Paused => Some("#9e9e9e"),
+            _ => None,
+        }
+    }
+
+    /// the color of a state, None for states without an accent
+    pub fn color(custom: &BTreeMap<String, String>, state: PomoState) -> Option<String> {
+        custom
+            .get(&state.to_string())
+            .cloned()
+            .or_else(|| Self::default_color(state).map(|color| color.into()))
+    }
+
+    /// stores a color in the preferences, the default is not stored
+    pub fn set(custom: &mut BTreeMap<String, String>, state: PomoState, color: &str) {
+        if Self::default_color(state) == Some(color) {
+            custom.remove(&state.to_string());
+        } else {
+            custom.insert(state.to_string(), color.into());
+        }
+    }
+}
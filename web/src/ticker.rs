@@ -0,0 +1,70 @@
+use gloo_timers::callback::{Interval, Timeout};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use yew_agent::{Agent, AgentLink, HandlerId, Public};
+
+/// Browsers throttle the timers of hidden tabs
+/// but not the timers of web workers,
+/// so the ui is ticked from a worker
+pub struct Ticker {
+    link: AgentLink<Self>,
+    timers: HashMap<HandlerId, Timers>,
+}
+
+/// the timers of a single bridge
+#[derive(Default)]
+struct Timers {
+    interval: Option<Interval>,
+    deadline: Option<Timeout>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum TickerInput {
+    /// ticks every millis
+    Every(u32),
+    /// ticks once after millis, replaces the previous deadline
+    Deadline(Option<u32>),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Tick;
+
+impl Agent for Ticker {
+    type Reach = Public<Self>;
+    type Message = HandlerId;
+    type Input = TickerInput;
+    type Output = Tick;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Self {
+            link,
+            timers: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, who: Self::Message) {
+        self.link.respond(who, Tick);
+    }
+
+    fn handle_input(&mut self, msg: Self::Input, who: HandlerId) {
+        let link = self.link.clone();
+        let timers = self.timers.entry(who).or_default();
+        match msg {
+            TickerInput::Every(millis) => {
+                timers.interval = Some(Interval::new(millis, move || link.send_message(who)));
+            }
+            TickerInput::Deadline(millis) => {
+                timers.deadline =
+                    millis.map(|millis| Timeout::new(millis, move || link.send_message(who)));
+            }
+        }
+    }
+
+    fn disconnected(&mut self, who: HandlerId) {
+        self.timers.remove(&who);
+    }
+
+    fn name_of_resource() -> &'static str {
+        "worker.js"
+    }
+}
@@ -0,0 +1,41 @@
+use gloo_timers::callback::Timeout;
+use yew::{html, Callback, Html};
+
+/// how long a toast is shown in milliseconds
+const TOAST_TIME: u32 = 5000;
+
+/// A message that dismisses itself after a while
+pub struct Toast {
+    pub id: usize,
+    pub message: String,
+    _timeout: Timeout,
+}
+
+impl Toast {
+    pub fn new(id: usize, message: String, dismiss: Callback<usize>) -> Self {
+        Self {
+            id,
+            message,
+            _timeout: Timeout::new(TOAST_TIME, move || dismiss.emit(id)),
+        }
+    }
+}
+
+/// Renders the toasts on top of the page, newest last
+pub fn view_toasts(toasts: &[Toast], dismiss: Callback<usize>) -> Html {
+    let toasts = toasts.iter().map(|toast| {
+        let id = toast.id;
+        html! {
+            <div class="notification is-danger">
+                <button class="delete" onclick={dismiss.reform(move |_| id)}></button>
+                { toast.message.clone() }
+            </div>
+        }
+    });
+
+    html! {
+        <div class="toasts">
+            { for toasts }
+        </div>
+    }
+}
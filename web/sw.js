@@ -0,0 +1,100 @@
+// Caches the app so it works offline.
+// Trunk hashes the names of the wasm bundle, so the bundle is found
+// by reading the links of index.html when the worker is installed.
+const CACHE = "pomododragon-v2";
+const SHELL = ["./", "./index.html", "./manifest.webmanifest"];
+// cached as well, but the install does not fail without them
+const ASSETS = ["./worker.js", "./worker_bg.wasm", "./icons/icon.svg", "./icons/icon-192.png", "./icons/icon-512.png"];
+
+// names trunk gave a content hash, e.g. pomododragon-web-1a2b3c4d5e6f7a8b_bg.wasm
+const HASHED = /-[0-9a-f]{16}(_bg)?\.(js|wasm|css)$/;
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    caches
+      .open(CACHE)
+      .then((cache) =>
+        cache
+          .addAll(SHELL)
+          .then(() => bundle())
+          .then((bundle) => Promise.all([...bundle, ...ASSETS].map((url) => cache.add(url).catch(() => undefined))))
+      )
+      .then(() => self.skipWaiting())
+  );
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) => Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key))))
+      .then(() => self.clients.claim())
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  const url = new URL(request.url);
+  if (request.method !== "GET" || url.origin !== self.location.origin) {
+    return;
+  }
+
+  if (request.mode === "navigate") {
+    // pages are fetched first so updates show up right away
+    event.respondWith(
+      fetch(request)
+        .then((response) => store(request, response))
+        .catch(() => caches.match(request).then((cached) => cached || caches.match("./")))
+    );
+  } else if (HASHED.test(url.pathname)) {
+    // hashed files never change
+    event.respondWith(
+      caches.match(request).then((cached) => cached || fetch(request).then((response) => store(request, response)))
+    );
+  } else {
+    // other files keep their name when they change,
+    // the cached one is served while it is refreshed
+    const update = fetch(request).then((response) => store(request, response));
+    event.waitUntil(update.catch(() => undefined));
+    event.respondWith(caches.match(request).then((cached) => cached || update));
+  }
+});
+
+// brings the app to the front when a notification is clicked
+self.addEventListener("notificationclick", (event) => {
+  event.notification.close();
+  event.waitUntil(
+    self.clients.matchAll({ type: "window", includeUncontrolled: true }).then((clients) => {
+      for (const client of clients) {
+        if ("focus" in client) {
+          return client.focus();
+        }
+      }
+      return self.clients.openWindow("./");
+    })
+  );
+});
+
+// the same origin scripts, styles and wasm files index.html refers to
+function bundle() {
+  return fetch("./index.html")
+    .then((response) => response.text())
+    .then((html) => {
+      const assets = new Set();
+      for (const match of html.matchAll(/(?:href|src)=["']([^"']+)["']|["']([^"']+\.(?:js|wasm|css))["']/g)) {
+        const url = new URL(match[1] || match[2], self.location.href);
+        if (url.origin === self.location.origin && HASHED.test(url.pathname)) {
+          assets.add(url.href);
+        }
+      }
+      return [...assets];
+    });
+}
+
+function store(request, response) {
+  if (response.ok) {
+    const copy = response.clone();
+    caches.open(CACHE).then((cache) => cache.put(request, copy));
+  }
+  return response;
+}